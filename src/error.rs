@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use crate::{ expr::Expr, stmt::Stmt };
+use crate::{ callable::{arguments, Arity}, expr::Expr, stmt::Stmt };
 
 #[derive(Error, Debug)]
 pub enum LexerError {
@@ -18,7 +18,20 @@ pub enum LexerError {
 
     // Occurs when the lexer reaches the end of the source but still expects another character
     #[error("Cannot peek when at the end of the source string on line {line}")]
-    CannotPeekAtTheEnd { line: usize }
+    CannotPeekAtTheEnd { line: usize },
+
+    // Occurs when a number literal stops before its digits, such as `0x` or `1e`
+    #[error("Expected digits after '{literal}' on line {line}")]
+    MissingDigits { literal: String, line: usize },
+
+    // Occurs when a number literal contains a character that is not a digit in its base, such as
+    // `0b12`
+    #[error("Invalid digit '{c}' in the number literal '{literal}' on line {line}")]
+    InvalidDigit { c: char, literal: String, line: usize },
+
+    // Occurs when a '_' in a number literal is not between two digits
+    #[error("Misplaced '_' in the number literal '{literal}' on line {line}")]
+    MisplacedDigitSeparator { literal: String, line: usize },
 }
 
 #[derive(Error, Debug)]
@@ -30,6 +43,12 @@ pub enum ParserError {
         line: usize,
     },
 
+    #[error("The constant '{name}' on line {line} must be given a value")]
+    ExpectedConstantInitializer {
+        name: String,
+        line: usize,
+    },
+
     #[error("Expected semicolon after '{lexeme}' on line {line}")]
     ExpectedSemicolonAfterVariableDeclaration {
         lexeme: String,
@@ -73,6 +92,12 @@ pub enum ParserError {
         line: usize,
     },
 
+    #[error("Expect ';' after yield value '{value}' on line {line}")]
+    ExpectedSemicolonAfterYieldValue {
+        value: String,
+        line: usize,
+    },
+
     #[error("Expect '(' after 'while' on line {line}")]
     ExpectedLParenAfterWhile {
         line: usize,
@@ -83,6 +108,7 @@ pub enum ParserError {
         line: usize,
     },
 
+    #[allow(dead_code)]
     #[error("Expected an alteration expression on line {line}")]
     ExpectedAlterationExpression {
         line: usize,
@@ -114,6 +140,12 @@ pub enum ParserError {
         line: usize,
     },
 
+    #[error("Unable to parse literal '{value}' to an int on line {line}")]
+    UnableToParseLiteralToInt {
+        value: String,
+        line: usize,
+    },
+
     #[error("Expected a string/number, got '{value}' on line {line}")]
     ExpectedStringOrNumber {
         value: String,
@@ -152,13 +184,121 @@ pub enum ParserError {
         line: usize,
     },
 
+    #[error("The parameter '{name}' on line {line} needs a default value, as it comes after a parameter with one")]
+    RequiredParameterAfterDefault {
+        name: String,
+        line: usize,
+    },
+
+    #[error("A variadic parameter must be the last parameter on line {line}")]
+    VariadicParameterNotLast {
+        line: usize,
+    },
+
+    #[error("Positional arguments cannot come after keyword arguments on line {line}")]
+    PositionalArgumentAfterKeyword {
+        line: usize,
+    },
+
+    #[error("Expect ')' after parameters on line {line}")]
+    ExpectedRParenAfterParameters {
+        line: usize,
+    },
+
+    #[error("Expect '{{' before function body on line {line}")]
+    ExpectedLBraceBeforeFunctionBody {
+        line: usize,
+    },
+
+    #[error("Expect '(' after 'def' in an anonymous function on line {line}")]
+    ExpectedLParenAfterDef {
+        line: usize,
+    },
+
+    #[error("Expect '=>' after the parameters of an arrow function on line {line}")]
+    ExpectedArrow {
+        line: usize,
+    },
+
+    #[error("Expect ':' after the first branch of a conditional expression on line {line}")]
+    ExpectedColonInConditional {
+        line: usize,
+    },
+
+    #[error("Expect '(' after 'match' on line {line}")]
+    ExpectedLParenAfterMatch {
+        line: usize,
+    },
+
+    #[error("Expect ')' after the value being matched on line {line}")]
+    ExpectedRParenAfterMatchValue {
+        line: usize,
+    },
+
+    #[error("Expect '{{' before the arms of a match on line {line}")]
+    ExpectedLBraceBeforeMatchArms {
+        line: usize,
+    },
+
+    #[error("Expect '=>' after the pattern of a match arm on line {line}")]
+    ExpectedArrowAfterPattern {
+        line: usize,
+    },
+
+    #[error("Expected a pattern, got '{value}' on line {line}")]
+    ExpectedPattern {
+        value: String,
+        line: usize,
+    },
+
+    #[error("Expect ']' after the items of a list pattern on line {line}")]
+    ExpectedRBrackAfterPatterns {
+        line: usize,
+    },
+
+    #[error("Expect '=' after the pattern of a destructuring declaration on line {line}")]
+    ExpectedEqualAfterPattern {
+        line: usize,
+    },
+
+    #[error("Expect a string with the path of the module after '{keyword}' on line {line}")]
+    ExpectedModulePath {
+        keyword: String,
+        line: usize,
+    },
+
+    #[error("Expect 'as' and a name after the path of an import on line {line}")]
+    ExpectedAsAfterImport {
+        line: usize,
+    },
+
+    #[error("Expect 'import' after the path in a 'from' import on line {line}")]
+    ExpectedImportAfterFrom {
+        line: usize,
+    },
+
+    #[error("Expect the name of an exported value on line {line}")]
+    ExpectedImportName {
+        line: usize,
+    },
+
+    #[error("Expect ';' after an import on line {line}")]
+    ExpectedSemicolonAfterImport {
+        line: usize,
+    },
+
+    #[error("Only variable, constant and function declarations can be exported, on line {line}")]
+    ExpectedDeclarationAfterExport {
+        line: usize,
+    },
+
     #[error("Expected ']' after the values of a list on line {line}")]
     ExpectedRBrackAfterValues {
         line: usize,
     },
 
-    #[error("Can only call methods on identifiers, not '{value}' on line {line}")]
-    CanOnlyCallIdentifiers {
+    #[error("Expected a method name after '.', not '{value}' on line {line}")]
+    ExpectedMethodName {
         value: String,
         line: usize,
     },
@@ -183,6 +323,31 @@ pub enum SemanticAnalyserError {
 
     #[error("Can't return outside of a function")]
     CannotReturnOutsideFunction,
+
+    #[error("Can only assign to a variable or a list element, not {target}")]
+    InvalidAssignmentTarget { target: Expr },
+
+    #[error("Can't yield outside of a function")]
+    CannotYieldOutsideFunction,
+
+    #[error("Cannot assign to {name}, as it is a constant")]
+    AssignmentToConstant { name: String },
+
+    #[error("Cannot call {method} on {name}, as it is a constant")]
+    ConstantChangedByMethod { name: String, method: String },
+
+    #[error("{name} can only be exported at the top level of a file")]
+    ExportOutsideTopLevel { name: String },
+
+    #[error("Every alternative of the pattern {pattern} must bind the same variables")]
+    InconsistentPatternBindings { pattern: String },
+}
+
+/// Problems found by the semantic analyser that do not stop the program from running
+#[derive(Error, Debug)]
+pub enum SemanticAnalyserWarning {
+    #[error("The arm '{pattern}' of the match on line {line} can never run, as an earlier arm matches every value it does")]
+    UnreachableMatchArm { pattern: String, line: usize },
 }
 
 #[derive(Error, Debug)]
@@ -193,9 +358,6 @@ pub enum InterpreterError {
     #[error("The expression provided ({expr}), was different to the expected ({expected})")]
     DifferentExpression { expr: Expr, expected: String },
 
-    #[error("Expected a literal value")]
-    ExpectedLiteralValue,
-
     #[error("Unable to negate number")]
     UnableToNegate,
 
@@ -205,6 +367,18 @@ pub enum InterpreterError {
     #[error("Expected a number")]
     ExpectedNumber,
 
+    #[error("Can only assign to a variable or a list element, not {target}")]
+    InvalidAssignmentTarget { target: Expr },
+
+    #[error("Cannot divide by zero")]
+    DivisionByZero,
+
+    #[error("Expected a whole number, but got {value}")]
+    ExpectedInteger { value: f64 },
+
+    #[error("Cannot shift by {amount} bits, as it must be between 0 and 63")]
+    InvalidShiftAmount { amount: i64 },
+
     #[error("Expected a valid binary operator")]
     ExpectedValidBinaryOperator,
 
@@ -216,42 +390,149 @@ pub enum InterpreterError {
         line: usize
     },
 
+    #[error("Cannot assign to {name} on line {line}, as it is a constant")]
+    AssignmentToConstant { name: String, line: usize },
+
+    #[error("Could not find the module '{path}' imported on line {line}")]
+    ModuleNotFound { path: String, line: usize },
+
+    #[error("Could not load the module '{path}': {message}")]
+    ModuleFailedToLoad { path: String, message: String },
+
+    #[error("{message} (in the module '{path}' on line {line})")]
+    ErrorInModule { path: String, line: usize, message: String },
+
+    #[error("Modules cannot import each other in a cycle: {chain}")]
+    CircularImport { chain: String },
+
+    #[error("The module '{module}' does not export '{name}'")]
+    NotExported { module: String, name: String },
+
+    #[error("Only a name or a function call can follow the module '{module}'")]
+    InvalidModuleMember { module: String },
+
+    // Only returned by `Interpreter::call_function`, which is part of the host API
+    #[allow(dead_code)]
+    #[error("There is no global named {name}")]
+    UndefinedGlobal { name: String },
+
     #[error("Expected an alteration token")]
     ExpectedAlterationToken,
 
     #[error("Expected to call a function/class, not a literal value")]
     ExpectedFunctionOrClass,
 
-    #[error("Expected {arity} arguments but got {args}")]
+    #[error("Expected {arity} {} but got {args}", arguments(*.arity))]
     ArgsDifferFromArity { args: usize, arity: usize },
 
     #[error("Expected the function declaration to be function statement")]
     ExpectedDeclarationToBeAFunction,
 
-    #[error("Expected to print out a literal value")]
-    ExpectedToPrintLiteralValue,
-
     #[error("Expected function declaration to be a function statement")]
     ExpectedFunctionStatementForDeclaration,
 
-    #[error("Expected the index to be a number value")]
-    ExpectedIndexToBeANum,
+    #[error("Expected the index to be an int")]
+    ExpectedIndexToBeAnInt,
 
     #[error("The list index was out of range")]
     IndexOutOfRange,
 
+    #[error("The generator '{name}' was resumed while it was already running")]
+    GeneratorAlreadyRunning { name: String },
+
+    #[error("Generators do not have a method called '{method}'")]
+    InvalidGeneratorMethod { method: String },
+
+    #[error("Can only yield inside a generator")]
+    YieldOutsideGenerator,
+
+    #[error("A range must have finite bounds and a step that is not 0")]
+    InvalidRange,
+
+    #[error("A range has too many items to count")]
+    RangeTooLarge,
+
+    #[error("The exponent {exponent} gives a number too large to work out")]
+    ExponentTooLarge { exponent: u64 },
+
+    #[error("A value of type '{type_name}' cannot be iterated over")]
+    NotIterable { type_name: String },
+
+    #[error("The list '{name}' cannot be modified while it is being iterated over")]
+    ListModifiedDuringIteration { name: String },
+
     #[error("The value cannot be indexed")]
     ValueWasNotAList,
 
     #[error("That method does not exist on a list")]
     InvalidListMethod,
 
+    #[error("Expected a function to be passed to '{method}'")]
+    ExpectedCallback { method: String },
+
+    #[error("Cannot call '{method}' on an empty list")]
+    EmptyList { method: String },
+
+    #[error("Expected the low bound of 'randomInt' to be at most the high bound, got {low} and {high}")]
+    InvalidRandomRange { low: i64, high: i64 },
+
+    #[error("Cannot take a sample of {k} items from a list of {len} items")]
+    SampleTooLarge { k: usize, len: usize },
+
+    #[error("Cannot take a sample of a negative number of items, got {k}")]
+    NegativeSampleSize { k: i64 },
+
+    #[error("Cannot convert {value} to {target}")]
+    InvalidConversion { value: String, target: String },
+
+    #[error("Invalid format string {template:?}: {message}")]
+    InvalidFormat { template: String, message: String },
+
+    #[error("Expected a list")]
+    ExpectedList,
+
+    #[error("Expected a string")]
+    ExpectedString,
+
+    #[error("Cannot destructure {element}: expected {expected}, got {got}")]
+    DestructuringFailed {
+        element: String,
+        expected: String,
+        got: String,
+    },
+
+    #[error("Cannot reduce an empty list without an initial value")]
+    ReduceOfEmptyList,
+
     #[error("The item could not be found in the list")]
     ItemNotFound,
 
-    #[error("The two values could not be compared")]
-    CannotCompareValues,
+    #[error("Cannot compare a {left} with a {right}")]
+    CannotCompareTypes { left: String, right: String },
+
+    #[error("Expected the comparator to return a number")]
+    ExpectedComparatorNumber,
+
+    #[error("Expected the last argument of 'sort' to be a bool for the descending flag")]
+    ExpectedSortFlag,
+
+    #[error("'{name}' expects {arity} but got {args}")]
+    ArgsOutsideArity { name: String, args: usize, arity: Arity },
 
-    #[error("The value passed in to the hash function must be a string")]
-    CannotHashValue,
+    #[error("'{function}' is missing the argument '{param}'")]
+    MissingArgument { function: String, param: String },
+
+    #[error("'{function}' does not have a parameter called '{name}'")]
+    UnknownKeywordArgument { function: String, name: String },
+
+    #[error("The argument '{name}' of '{function}' was given more than once")]
+    DuplicateArgument { function: String, name: String },
+
+    #[error("Expected parameter '{param}' of '{function}' to be {expected}, got {got}")]
+    MismatchedArgumentType {
+        function: String,
+        param: String,
+        expected: String,
+        got: String,
+    },
 }
@@ -1,6 +1,6 @@
 use std::{
     cell::RefCell,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::{self, Debug},
     rc::Rc,
 };
@@ -15,6 +15,7 @@ use crate::{
 #[derive(Debug)]
 pub struct Environment {
     values: HashMap<String, Rc<RefCell<Value>>>,
+    constants: HashSet<String>, // The names of values declared with 'const'
     enclosing: Option<Env>
 }
 
@@ -22,14 +23,49 @@ impl Environment {
     pub fn new(enclosing: Option<Env>) -> Self {
         return Self {
             values: HashMap::new(),
+            constants: HashSet::new(),
             enclosing,
         }
     }
 
     pub fn define(&mut self, name: String, value: Value) {
+        self.constants.remove(&name);
         self.values.insert(name, Rc::new(RefCell::new(value)));
     }
 
+    /// Defines a value that cannot be assigned to afterwards
+    pub fn define_const(&mut self, name: String, value: Value) {
+        self.values.insert(name.clone(), Rc::new(RefCell::new(value)));
+        self.constants.insert(name);
+    }
+
+    /// The names of every value defined directly in this environment
+    pub fn names(&self) -> Vec<String> {
+        return self.values.keys().cloned().collect();
+    }
+
+    /// Looks up a value by name without needing a token, for use by the host application
+    pub fn get_value(&self, name: &str) -> Option<Value> {
+        return match self.values.get(name) {
+            Some(v) => Some(v.borrow().clone()),
+            None => match &self.enclosing {
+                Some(enclosing) => enclosing.borrow().get_value(name),
+                None => None,
+            },
+        };
+    }
+
+    /// Finds the environment that `name` is defined in, starting from `env`
+    pub fn resolve(env: &Env, name: &str) -> Option<Env> {
+        if env.borrow().values.contains_key(name) {
+            return Some(Rc::clone(env));
+        }
+        return match &env.borrow().enclosing {
+            Some(enclosing) => Environment::resolve(enclosing, name),
+            None => None,
+        };
+    }
+
     pub fn get(&self, name: Token) -> Result<Value, InterpreterError> {
         return match self.values.get(&name.lexeme) {
             Some(v) => Ok(v.borrow().clone()),
@@ -48,10 +84,19 @@ impl Environment {
         }
     }
 
+    /// Assigns to an existing value. The semantic analyser rejects assignments to constants, but
+    /// they are checked here as well for values it cannot see, such as globals declared by an
+    /// earlier run in the same interpreter
     pub fn assign(&mut self, name: Token, value: Value) -> Result<Value, InterpreterError> {
-        if self.values.contains_key(&name.lexeme) {
-            self.values
-                .insert(name.lexeme, Rc::new(RefCell::new(value.clone())));
+        if self.constants.contains(&name.lexeme) {
+            return Err(InterpreterError::AssignmentToConstant {
+                name: name.lexeme,
+                line: name.line,
+            });
+        }
+
+        if let Some(v) = self.values.get_mut(&name.lexeme) {
+            *v = Rc::new(RefCell::new(value.clone()));
             return Ok(value);
         }
 
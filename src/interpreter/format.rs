@@ -0,0 +1,159 @@
+//! The `format` builtin, which fills in the placeholders of a template string, e.g.
+//! `format("{} has {:.2} items", name, n)`.
+//!
+//! A placeholder is `{}` for the next argument, `{0}` for an argument by its position, or `{name}`
+//! for a keyword argument, as in `format("{name}!", name: "Ada")`. It can end with a spec after a colon, written
+//! `[[fill]align][width][.precision]`:
+//! - `align` is `<`, `>` or `^` for left, right or centre. Numbers are aligned right by default,
+//!   and everything else left
+//! - `fill` is the character used to pad up to `width`, which is a space by default
+//! - `precision` is the number of decimal places for a number, or the most characters to show of
+//!   any other value
+//!
+//! `{{` and `}}` are written as literal braces.
+
+use std::collections::VecDeque;
+
+use crate::{
+    callable::{Arity, KeywordArgs, NativeFunc},
+    convert::NativeParam,
+    error::InterpreterError,
+    interpreter::Interpreter,
+    number::Number,
+    value::Value,
+};
+
+/// Registers the `format` builtin
+pub fn register(interpreter: &mut Interpreter) {
+    let format = NativeFunc::with_keywords("format".to_string(), Arity::Variadic(1), |_, args, keywords| {
+        let mut args = VecDeque::from(args);
+        let template = String::take(&mut args, "format", "template")?;
+        return format(&template, &Vec::from(args), &keywords).map(Value::from);
+    });
+
+    interpreter.define_builtin("format", Value::NativeFunction(format));
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Right,
+    Centre,
+}
+
+/// The part of a placeholder after the colon
+struct Spec {
+    fill: char,
+    align: Option<Align>,
+    width: usize,
+    precision: Option<usize>,
+}
+
+impl Spec {
+    fn parse(spec: &str) -> Option<Self> {
+        let chars: Vec<char> = spec.chars().collect();
+        let align_of = |c: Option<&char>| match c {
+            Some('<') => Some(Align::Left),
+            Some('>') => Some(Align::Right),
+            Some('^') => Some(Align::Centre),
+            _ => None,
+        };
+
+        let (fill, align, rest) = match (align_of(chars.get(1)), align_of(chars.first())) {
+            (Some(align), _) => (chars[0], Some(align), &chars[2..]),
+            (None, Some(align)) => (' ', Some(align), &chars[1..]),
+            (None, None) => (' ', None, &chars[..]),
+        };
+
+        let rest: String = rest.iter().collect();
+        let (width, precision) = match rest.split_once('.') {
+            Some((width, precision)) => (width, Some(precision.parse::<usize>().ok()?)),
+            None => (rest.as_str(), None),
+        };
+        let width = match width {
+            "" => 0,
+            width => width.parse::<usize>().ok()?,
+        };
+
+        return Some(Self { fill, align, width, precision });
+    }
+
+    fn apply(&self, value: &Value) -> String {
+        let number = Number::from_value(value);
+        let text = match (self.precision, &number) {
+            (Some(precision), Some(n)) => format!("{:.precision$}", n.to_f64()),
+            (Some(precision), None) => value.to_string().chars().take(precision).collect(),
+            (None, _) => value.to_string(),
+        };
+
+        let padding = self.width.saturating_sub(text.chars().count());
+        let default = if number.is_some() { Align::Right } else { Align::Left };
+        let (before, after) = match self.align.unwrap_or(default) {
+            Align::Left => (0, padding),
+            Align::Right => (padding, 0),
+            Align::Centre => (padding / 2, padding - padding / 2),
+        };
+
+        let fill = |n: usize| self.fill.to_string().repeat(n);
+        return format!("{}{text}{}", fill(before), fill(after));
+    }
+}
+
+fn format(template: &str, args: &[Value], keywords: &KeywordArgs) -> Result<String, InterpreterError> {
+    let invalid = |message: String| InterpreterError::InvalidFormat {
+        template: template.to_string(),
+        message,
+    };
+
+    let mut result = String::new();
+    let mut chars = template.chars().peekable();
+    let mut next = 0; // The argument used by the next '{}'
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                result.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                result.push('}');
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err(invalid("a '{' is never closed".to_string())),
+                    }
+                }
+
+                let (name, spec) = placeholder.split_once(':').unwrap_or((&placeholder, ""));
+                let value = if name.is_empty() {
+                    next += 1;
+                    args.get(next - 1)
+                        .cloned()
+                        .ok_or_else(|| invalid(format!("there is no argument {}", next - 1)))?
+                } else if let Ok(index) = name.parse::<usize>() {
+                    args.get(index)
+                        .cloned()
+                        .ok_or_else(|| invalid(format!("there is no argument {index}")))?
+                } else {
+                    keywords.iter()
+                        .find(|(keyword, _)| keyword == name)
+                        .map(|(_, value)| value.clone())
+                        .ok_or_else(|| invalid(format!("there is no argument called '{name}'")))?
+                };
+
+                let spec = Spec::parse(spec)
+                    .ok_or_else(|| invalid(format!("'{spec}' is not a valid format spec")))?;
+                result.push_str(&spec.apply(&value));
+            }
+            '}' => return Err(invalid("a '}' outside a placeholder must be written as '}}'".to_string())),
+            c => result.push(c),
+        }
+    }
+
+    return Ok(result);
+}
@@ -0,0 +1,369 @@
+//! Generators are created by calling a function that contains a `yield` statement. Instead of
+//! running the body, the call returns a generator, which runs the body up to the next `yield`
+//! each time another value is asked for.
+//!
+//! The interpreter normally executes statements recursively, which means that a function body
+//! cannot be paused half way through. A generator's body is instead run by a small resumable
+//! executor, which keeps its position in a stack of frames rather than on the Rust call stack:
+//!
+//! - A block frame stores the statements of a block and the index of the next one to run
+//! - A loop frame stores the condition, increment and body of a `while` or `for` loop
+//! - A for in frame stores the iterator being looped over
+//!
+//! Only statements that contain a `yield` are broken down into frames. Every other statement is
+//! executed by the interpreter as usual, in the environment of the frame it belongs to.
+
+use std::{
+    cell::RefCell,
+    fmt,
+    rc::Rc,
+};
+
+use crate::{
+    enviromnent::Environment,
+    error::InterpreterError,
+    expr::Expr,
+    interpreter::{Env, Interpreter},
+    iterator::ValueIter,
+    stmt::Stmt,
+    token::Token,
+    value::Value,
+};
+
+/// A lazy sequence of values, which is shared between every variable that refers to it
+#[derive(Clone)]
+pub struct Generator {
+    name: String,
+    state: Rc<RefCell<GeneratorState>>,
+}
+
+enum GeneratorState {
+    Frames(Vec<Frame>),
+    Map { source: Generator, function: Value },
+    Filter { source: Generator, function: Value },
+    Take { source: Generator, remaining: usize },
+    Done,
+}
+
+// A for-in frame holds the iterator of its loop, which may hold a function, so it is much larger
+// than the others. Frames are only kept in a short stack for each generator, so it is left unboxed
+#[allow(clippy::large_enum_variant)]
+enum Frame {
+    Block {
+        statements: Vec<Stmt>,
+        index: usize,
+        environment: Env,
+    },
+    Loop {
+        condition: Expr,
+        increment: Option<Expr>,
+        body: Stmt,
+        environment: Env,
+        started: bool,
+    },
+    ForIn {
+        name: Token,
+        iter: ValueIter,
+        body: Stmt,
+        environment: Env,
+        lock: Option<(Env, String)>, // The list variable looped over, see `Interpreter::iteration_lock`
+    },
+}
+
+impl Generator {
+    /// Creates a generator that runs `body` in `environment`, which already holds the arguments
+    pub fn new(name: String, body: Vec<Stmt>, environment: Env) -> Self {
+        let frame = Frame::Block {
+            statements: body,
+            index: 0,
+            environment,
+        };
+        return Self::from_state(name, GeneratorState::Frames(vec![frame]));
+    }
+
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+
+    /// A generator that calls `function` on each value of this one
+    pub fn map(&self, function: Value) -> Self {
+        let state = GeneratorState::Map { source: self.clone(), function };
+        return Self::from_state("map".to_string(), state);
+    }
+
+    /// A generator of the values of this one that `function` returns a truthy value for
+    pub fn filter(&self, function: Value) -> Self {
+        let state = GeneratorState::Filter { source: self.clone(), function };
+        return Self::from_state("filter".to_string(), state);
+    }
+
+    /// A generator of at most the next `count` values of this one
+    pub fn take(&self, count: usize) -> Self {
+        let state = GeneratorState::Take { source: self.clone(), remaining: count };
+        return Self::from_state("take".to_string(), state);
+    }
+
+    fn from_state(name: String, state: GeneratorState) -> Self {
+        return Self {
+            name,
+            state: Rc::new(RefCell::new(state)),
+        };
+    }
+
+    /// Runs the generator until it produces its next value. Returns `None` once it has finished,
+    /// which happens when the body ends or returns, or if it raises an error
+    pub fn resume(&self, interpreter: &mut Interpreter) -> Result<Option<Value>, InterpreterError> {
+        let mut state = match self.state.try_borrow_mut() {
+            Ok(s) => s,
+            Err(_) => return Err(InterpreterError::GeneratorAlreadyRunning { name: self.name.clone() }),
+        };
+
+        let result = match &mut *state {
+            GeneratorState::Frames(frames) => {
+                // The lists looped over by the body are only locked while it is running, as the
+                // caller is free to change them while the generator is suspended
+                let depth = interpreter.iterating.len();
+                for frame in frames.iter() {
+                    if let Frame::ForIn { lock: Some(lock), .. } = frame {
+                        interpreter.iterating.push(lock.clone());
+                    }
+                }
+
+                let result = run_frames(frames, interpreter);
+                interpreter.iterating.truncate(depth);
+                result
+            }
+            GeneratorState::Map { source, function } => match source.resume(interpreter)? {
+                Some(value) => Ok(Some(interpreter.call_value(function.clone(), vec![value])?)),
+                None => Ok(None),
+            },
+            GeneratorState::Filter { source, function } => loop {
+                match source.resume(interpreter)? {
+                    Some(value) => {
+                        let keep = interpreter.call_value(function.clone(), vec![value.clone()])?;
+                        if interpreter.is_truthy(&keep)? {
+                            break Ok(Some(value));
+                        }
+                    }
+                    None => break Ok(None),
+                }
+            },
+            GeneratorState::Take { source, remaining } => {
+                if *remaining == 0 {
+                    Ok(None)
+                } else {
+                    *remaining -= 1;
+                    source.resume(interpreter)
+                }
+            }
+            GeneratorState::Done => Ok(None),
+        };
+
+        if !matches!(result, Ok(Some(_))) {
+            *state = GeneratorState::Done;
+        }
+        return result;
+    }
+}
+
+/// Whether a statement contains a `yield`, not counting any functions declared inside it, which
+/// makes a function containing it a generator
+pub fn contains_yield(stmt: &Stmt) -> bool {
+    return match stmt {
+        Stmt::Yield { .. } => true,
+        Stmt::Block { statements } => statements.iter().any(contains_yield),
+        Stmt::If { then_branch, else_branch, .. } => {
+            contains_yield(then_branch) || else_branch.as_ref().is_some_and(|s| contains_yield(s))
+        }
+        Stmt::Match { arms, .. } => arms.iter().any(|arm| contains_yield(&arm.body)),
+        Stmt::For { body, .. } | Stmt::ForIn { body, .. } | Stmt::While { body, .. } => {
+            contains_yield(body)
+        }
+        _ => false,
+    };
+}
+
+/// Runs the frames until a value is yielded, or there are no frames left
+fn run_frames(frames: &mut Vec<Frame>, interpreter: &mut Interpreter) -> Result<Option<Value>, InterpreterError> {
+    loop {
+        let (stmt, environment) = match frames.last_mut() {
+            None => return Ok(None),
+            Some(Frame::Block { statements, index, environment }) => {
+                if *index >= statements.len() {
+                    frames.pop();
+                    continue;
+                }
+                *index += 1;
+                (statements[*index - 1].clone(), Rc::clone(environment))
+            }
+            Some(Frame::Loop { condition, increment, body, environment, started }) => {
+                if *started {
+                    if let Some(increment) = increment {
+                        interpreter.evaluate_in(increment, Rc::clone(environment))?;
+                    }
+                }
+                *started = true;
+
+                let value = interpreter.evaluate_in(condition, Rc::clone(environment))?;
+                if !interpreter.is_truthy(&value)? {
+                    frames.pop();
+                    continue;
+                }
+
+                let frame = Frame::Block {
+                    statements: vec![body.clone()],
+                    index: 0,
+                    environment: Rc::clone(environment),
+                };
+                frames.push(frame);
+                continue;
+            }
+            Some(Frame::ForIn { name, iter, body, environment, lock }) => {
+                let item = match iter.next(interpreter)? {
+                    Some(v) => v,
+                    None => {
+                        if lock.is_some() {
+                            interpreter.iterating.pop();
+                        }
+                        frames.pop();
+                        continue;
+                    }
+                };
+
+                let scope = Rc::new(RefCell::new(Environment::new(Some(Rc::clone(environment)))));
+                scope.borrow_mut().define(name.lexeme.clone(), item);
+
+                let frame = Frame::Block {
+                    statements: vec![body.clone()],
+                    index: 0,
+                    environment: scope,
+                };
+                frames.push(frame);
+                continue;
+            }
+        };
+
+        if let Some(value) = step(frames, interpreter, stmt, environment)? {
+            return Ok(Some(value));
+        }
+    }
+}
+
+/// Runs a single statement of a generator's body, pushing a new frame if it contains a `yield`
+fn step(
+    frames: &mut Vec<Frame>,
+    interpreter: &mut Interpreter,
+    stmt: Stmt,
+    environment: Env,
+) -> Result<Option<Value>, InterpreterError> {
+    if !contains_yield(&stmt) {
+        return match interpreter.execute_in(&stmt, environment) {
+            Ok(()) => Ok(None),
+            // A return statement finishes the generator, and its value is ignored
+            Err(Ok(_)) => {
+                frames.clear();
+                Ok(None)
+            }
+            Err(Err(e)) => Err(e),
+        };
+    }
+
+    match stmt {
+        Stmt::Yield { keyword: _, value } => {
+            return Ok(Some(interpreter.evaluate_in(&value, environment)?));
+        }
+        Stmt::Block { statements } => {
+            let scope = Rc::new(RefCell::new(Environment::new(Some(environment))));
+            frames.push(Frame::Block { statements, index: 0, environment: scope });
+        }
+        Stmt::If { condition, then_branch, else_branch } => {
+            let value = interpreter.evaluate_in(&condition, Rc::clone(&environment))?;
+            let branch = if interpreter.is_truthy(&value)? {
+                Some(*then_branch)
+            } else {
+                else_branch.map(|s| *s)
+            };
+
+            if let Some(branch) = branch {
+                frames.push(Frame::Block { statements: vec![branch], index: 0, environment });
+            }
+        }
+        Stmt::Match { value, arms, .. } => {
+            let value = interpreter.evaluate_in(&value, Rc::clone(&environment))?;
+            if let Some((body, scope)) = interpreter.match_arm(&value, &arms, environment)? {
+                frames.push(Frame::Block { statements: vec![body], index: 0, environment: scope });
+            }
+        }
+        Stmt::While { condition, body } => {
+            frames.push(Frame::Loop {
+                condition,
+                increment: None,
+                body: *body,
+                environment,
+                started: false,
+            });
+        }
+        Stmt::For { initializer, condition, increment, body } => {
+            let scope = Rc::new(RefCell::new(Environment::new(Some(environment))));
+            if let Some(initializer) = initializer {
+                if let Err(Err(e)) = interpreter.execute_in(&initializer, Rc::clone(&scope)) {
+                    return Err(e);
+                }
+            }
+
+            frames.push(Frame::Loop {
+                condition,
+                increment,
+                body: *body,
+                environment: scope,
+                started: false,
+            });
+        }
+        Stmt::ForIn { name, iterable, body } => {
+            let value = interpreter.evaluate_in(&iterable, Rc::clone(&environment))?;
+            let lock = Interpreter::iteration_lock(&environment, &iterable, &value);
+            let iter = ValueIter::new(value)?;
+            if let Some(lock) = &lock {
+                interpreter.iterating.push(lock.clone());
+            }
+
+            frames.push(Frame::ForIn {
+                name,
+                iter,
+                body: *body,
+                environment,
+                lock,
+            });
+        }
+        _ => {}
+    }
+
+    return Ok(None);
+}
+
+impl PartialEq for Generator {
+    fn eq(&self, other: &Self) -> bool {
+        return Rc::ptr_eq(&self.state, &other.state);
+    }
+}
+
+impl PartialOrd for Generator {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        if self == other {
+            return Some(std::cmp::Ordering::Equal);
+        }
+        return None;
+    }
+}
+
+impl fmt::Debug for Generator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "Generator({})", self.name);
+    }
+}
+
+impl fmt::Display for Generator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "Generator({})", self.name);
+    }
+}
@@ -1,21 +1,39 @@
 use std::{
     cell::RefCell,
+    path::Path,
     rc::Rc,
     time::{SystemTime, UNIX_EPOCH},
 };
 use sha2::{Sha256, Digest};
 
 use crate::{
-    alteration,
     arithmetic,
-    callable::{Callable, Func, NativeFunc},
+    bitwise,
+    callable::{Func, KeywordArgs, NativeFunc},
     comparison,
+    convert::NativeHandler,
     enviromnent::Environment,
     error::InterpreterError,
     expr::{self, Expr},
+    format,
+    generator::Generator,
+    iterator::ValueIter,
+    lexer::Lexer,
     list::List,
+    loader::ModuleLoader,
+    math,
+    module::Module,
+    number::Number,
+    options::InterpreterOptions,
+    output::{Output, StdOutput},
+    parser::Parser,
+    pattern::MatchArm,
+    random::{self, Random},
+    range::Range,
+    semanticanalyser::SemanticAnalyser,
     stmt::{self, Stmt},
-    token::TokenType,
+    token::{Token, TokenType},
+    types,
     value::{LiteralType, Value},
 };
 
@@ -23,45 +41,160 @@ pub type ExprResult = Result<Value, InterpreterError>;
 pub type StmtResult = Result<(), ExprResult>;
 pub type Env = Rc<RefCell<Environment>>;
 
+/// A variable or list element that a value can be assigned to
+enum Place {
+    Var(Token),
+    Index(Token, usize),
+}
+
 pub struct Interpreter {
+    /// The native functions, which every module's global environment encloses
+    pub builtins: Env,
     pub globals: Env,
     pub environment: Env,
-    test_output: Vec<String>,
+    pub options: InterpreterOptions,
+    output: Box<dyn Output>,
+    /// The list variables currently being looped over by `for in` loops, along with the
+    /// environment they are defined in
+    pub iterating: Vec<(Env, String)>,
+    modules: ModuleLoader,
+    random: Option<Random>, // Created the first time a random number is needed, from the seed in the options if there is one
+    line: usize, // The line of the code being run, so that errors inside a module can say where they happened
 }
 
 impl Interpreter {
     pub fn new() -> Self {
-        let global = Rc::new(RefCell::new(Environment::new(None)));
-
-        let clock = NativeFunc::new("clock".to_string(), 0, |_, _| {
-            Ok(Value::Literal(LiteralType::Num(
-                SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs_f64(),
-            )))
+        return Self::with_output(StdOutput);
+    }
+
+    /// Creates an interpreter that writes the script's output to `output` instead of stdout
+    pub fn with_output(output: impl Output + 'static) -> Self {
+        let builtins = Rc::new(RefCell::new(Environment::new(None)));
+        let global = Rc::new(RefCell::new(Environment::new(Some(Rc::clone(&builtins)))));
+
+        let mut interpreter = Self {
+            builtins,
+            globals: Rc::clone(&global),
+            environment: Rc::clone(&global),
+            options: InterpreterOptions::default(),
+            output: Box::new(output),
+            iterating: Vec::new(),
+            modules: ModuleLoader::default(),
+            random: None,
+            line: 0,
+        };
+
+        interpreter.register_fn("clock", &[], || {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs_f64()
         });
 
-        let hash = NativeFunc::new("hash".to_string(), 1, |_, args| {
-            if let Value::Literal(LiteralType::Str(s)) = &args[0] {
-                let mut hasher = Sha256::new();
-                hasher.update(s);
-                return Ok(Value::Literal(LiteralType::Str(format!("{:x}", hasher.finalize()))));
-            }
-            return Err(InterpreterError::CannotHashValue);
+        interpreter.register_fn("hash", &["value"], |value: String| {
+            let mut hasher = Sha256::new();
+            hasher.update(value);
+            format!("{:x}", hasher.finalize())
         });
 
-        global.borrow_mut().define("clock".to_string(), Value::NativeFunction(clock));
-        global.borrow_mut().define("hash".to_string(), Value::NativeFunction(hash));
+        interpreter.define_builtin("math", Value::Module(math::module()));
+        random::register(&mut interpreter);
+        types::register(&mut interpreter);
+        format::register(&mut interpreter);
 
-        return Self {
-            globals: Rc::clone(&global),
-            environment: Rc::clone(&global),
-            test_output: Vec::new()
+        return interpreter;
+    }
+
+    /// Registers a Rust closure as a native function, which can be used by the script and by
+    /// every module it imports.
+    ///
+    /// The closure's parameters and return value are converted to and from script values through
+    /// `FromValue` and `IntoValue`, so `Option<T>` parameters are optional and a trailing
+    /// `Rest<T>` parameter collects any remaining arguments. `params` names each parameter, and
+    /// is used when an argument cannot be converted to the type the closure expects.
+    pub fn register_fn<Args, F>(&mut self, name: &str, params: &[&str], fun: F)
+    where
+        F: NativeHandler<Args> + 'static,
+    {
+        let native = NativeFunc::from_handler(name, params, fun);
+        self.define_builtin(name, Value::NativeFunction(native));
+    }
+
+    /// Defines a constant in the builtins, which every module can see and shadow but cannot
+    /// reassign
+    pub fn define_builtin(&mut self, name: &str, value: Value) {
+        self.builtins.borrow_mut().define_const(name.to_string(), value);
+    }
+
+    /// The generator used by the random number builtins, seeded from `options.seed` or the clock
+    /// when it is first used
+    pub fn random(&mut self) -> &mut Random {
+        let seed = self.options.seed;
+        return self.random.get_or_insert_with(|| match seed {
+            Some(seed) => Random::new(seed),
+            None => Random::from_clock(),
+        });
+    }
+
+    /// Restarts the random number generator from `seed`, so the same numbers come out again
+    pub fn seed_random(&mut self, seed: u64) {
+        self.random = Some(Random::new(seed));
+    }
+
+    /// Defines a variable in the global environment, replacing any existing value
+    #[allow(dead_code)] // Host API, only used by the tests within this binary
+    pub fn set_global(&mut self, name: &str, value: impl Into<Value>) {
+        self.globals.borrow_mut().define(name.to_string(), value.into());
+    }
+
+    /// Returns the value of a global variable, or `None` if it has not been defined
+    #[allow(dead_code)] // Host API
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        return self.globals.borrow().get_value(name);
+    }
+
+    /// Calls a global function, which can either be declared by the script or be native
+    #[allow(dead_code)] // Host API
+    pub fn call_function(&mut self, name: &str, args: Vec<Value>) -> ExprResult {
+        let callee = match self.get_global(name) {
+            Some(v) => v,
+            None => return Err(InterpreterError::UndefinedGlobal { name: name.to_string() }),
         };
+
+        return self.call_value(callee, args);
+    }
+
+    /// Calls a function value with arguments that have already been evaluated, after checking
+    /// that the number of arguments matches its arity
+    pub fn call_value(&mut self, callee: Value, args: Vec<Value>) -> ExprResult {
+        return self.call_value_with(callee, args, Vec::new());
+    }
+
+    /// Calls a function value with positional and keyword arguments that have already been
+    /// evaluated
+    pub fn call_value_with(&mut self, callee: Value, args: Vec<Value>, keywords: KeywordArgs) -> ExprResult {
+        match callee {
+            Value::Function(f) => return f.call_with(self, args, keywords),
+            Value::NativeFunction(nf) => return nf.call_with(self, args, keywords),
+            _ => return Err(InterpreterError::ExpectedFunctionOrClass),
+        }
+    }
+
+    #[allow(dead_code)] // Host API
+    pub fn set_output(&mut self, output: impl Output + 'static) {
+        self.output = Box::new(output);
+    }
+
+    pub fn output(&mut self) -> &mut dyn Output {
+        return self.output.as_mut();
     }
 
-    pub fn interpret(&mut self, statements: Vec<Stmt>) -> Result<Vec<String>, InterpreterError> {
+    /// The line of the code being run
+    pub fn line(&self) -> usize {
+        return self.line;
+    }
+
+    pub fn interpret(&mut self, statements: Vec<Stmt>) -> Result<(), InterpreterError> {
         for stmt in statements {
             match self.execute(&stmt) {
                 Ok(()) => {}
@@ -71,7 +204,7 @@ impl Interpreter {
                 },
             };
         }
-        return Ok(self.test_output.clone());
+        return Ok(());
     }
 
     fn evaluate(&mut self, expr: &Expr) -> Result<Value, InterpreterError> {
@@ -85,6 +218,22 @@ impl Interpreter {
         return stmt.accept_stmt(self);
     }
 
+    /// Evaluates an expression in `environment` rather than the current environment
+    pub fn evaluate_in(&mut self, expr: &Expr, environment: Env) -> ExprResult {
+        let previous = std::mem::replace(&mut self.environment, environment);
+        let result = self.evaluate(expr);
+        self.environment = previous;
+        return result;
+    }
+
+    /// Executes a statement in `environment` rather than the current environment
+    pub fn execute_in(&mut self, stmt: &Stmt, environment: Env) -> StmtResult {
+        let previous = std::mem::replace(&mut self.environment, environment);
+        let result = self.execute(stmt);
+        self.environment = previous;
+        return result;
+    }
+
     pub fn execute_block(&mut self, statements: Vec<Stmt>, environment: Env) -> StmtResult {
         let previous = Rc::clone(&self.environment);
 
@@ -93,63 +242,608 @@ impl Interpreter {
         for statement in statements {
             match self.execute(&statement) {
                 Ok(_) => {}
-                Err(r) => match r {
-                    Ok(v) => {
-                        self.environment = previous;
-                        return Err(Ok(v));
-                    }
-                    Err(e) => return Err(Err(e)),
-                },
+                Err(r) => {
+                    self.environment = previous;
+                    return Err(r);
+                }
             }
         }
         self.environment = previous;
         return Ok(());
     }
 
-    fn is_truthy(&mut self, object: &Value) -> Result<bool, InterpreterError> {
-        match object {
-            Value::Literal(literal) => {
-                return Ok(!matches!(literal, LiteralType::Null | LiteralType::False))
+    /// Runs the body of a `for in` loop once for each item, with the item bound to `name` in a
+    /// new environment
+    fn for_in(&mut self, name: &Token, iter: &mut ValueIter, body: &Stmt) -> StmtResult {
+        loop {
+            let item = match iter.next(self) {
+                Ok(Some(v)) => v,
+                Ok(None) => return Ok(()),
+                Err(e) => return Err(Err(e)),
+            };
+
+            let environment = Rc::new(RefCell::new(Environment::new(Some(
+                self.environment.clone(),
+            ))));
+            environment.borrow_mut().define(name.lexeme.clone(), item);
+
+            self.execute_block(vec![body.clone()], environment)?;
+        }
+    }
+
+    /// Finds the first arm whose pattern matches the value and whose guard is truthy, returning
+    /// its body along with a new environment inside `enclosing` holding the variables bound by the
+    /// pattern
+    pub fn match_arm(
+        &mut self,
+        value: &Value,
+        arms: &[MatchArm],
+        enclosing: Env,
+    ) -> Result<Option<(Stmt, Env)>, InterpreterError> {
+        for arm in arms {
+            let mut bindings = Vec::new();
+            if !arm.pattern.matches(value, &mut bindings) {
+                continue;
+            }
+
+            let environment = Rc::new(RefCell::new(Environment::new(Some(Rc::clone(&enclosing)))));
+            for (name, value) in bindings {
+                environment.borrow_mut().define(name.lexeme, value);
+            }
+
+            if let Some(guard) = &arm.guard {
+                let result = self.evaluate_in(guard, environment.clone())?;
+                if !self.is_truthy(&result)? {
+                    continue;
+                }
             }
-            _ => return Err(InterpreterError::ExpectedLiteralValue),
+
+            return Ok(Some((arm.body.clone(), environment)));
         }
+
+        return Ok(None);
     }
 
-    fn is_equal(&mut self, a: &Value, b: &Value) -> bool {
-        return *a == *b;
+    /// Finds the module imported by `path` and gives it back, running the file first if this is
+    /// the first time it has been imported
+    fn import_module(&mut self, path: &Token) -> Result<Module, InterpreterError> {
+        let name = path.literal.clone();
+        let file = match self.modules.resolve(&name, &self.options.module_paths) {
+            Some(file) => file,
+            None => return Err(InterpreterError::ModuleNotFound { path: name, line: path.line }),
+        };
+
+        if let Some(module) = self.modules.cached(&file) {
+            return Ok(module);
+        }
+
+        self.modules.begin(file.clone(), &name)?;
+        let result = self.run_module(&file, &name);
+        self.modules.end(result.as_ref().ok().cloned());
+        return result;
+    }
+
+    /// Runs a module's file in a new global environment. Errors while running it are given the
+    /// module's name and the line they happened on, unless they already come from a module it
+    /// imports
+    fn run_module(&mut self, file: &Path, name: &str) -> Result<Module, InterpreterError> {
+        let failed = |message: String| InterpreterError::ModuleFailedToLoad {
+            path: name.to_string(),
+            message,
+        };
+
+        let source = std::fs::read_to_string(file).map_err(|e| failed(e.to_string()))?;
+        let tokens = Lexer::new(source).run().map_err(|e| failed(e.to_string()))?;
+        let ast = Parser::new(tokens).parse().map_err(|e| failed(e.to_string()))?;
+
+        let mut semantic_analyser = SemanticAnalyser::new(ast.clone());
+        semantic_analyser.declare_builtins(self.builtins.borrow().names());
+        semantic_analyser.run().map_err(|e| failed(e.to_string()))?;
+        for warning in semantic_analyser.warnings() {
+            self.output.write_err(&format!("Warning: {warning} (in the module '{name}')"));
+        }
+
+        let exports: Vec<String> = ast.iter().flat_map(exported_names).collect();
+        let environment = Rc::new(RefCell::new(Environment::new(Some(Rc::clone(&self.builtins)))));
+
+        let previous = std::mem::replace(&mut self.environment, Rc::clone(&environment));
+        let previous_line = self.line;
+        let result = self.interpret(ast);
+        self.environment = previous;
+        let line = std::mem::replace(&mut self.line, previous_line);
+
+        return match result {
+            Ok(()) => Ok(Module::new(name.to_string(), environment, exports)),
+            Err(e @ InterpreterError::ErrorInModule { .. }) => Err(e),
+            Err(e) => Err(InterpreterError::ErrorInModule {
+                path: name.to_string(),
+                line,
+                message: e.to_string(),
+            }),
+        };
+    }
+
+    /// Gets a member of a module, either as a value with `module.name` or by calling it with
+    /// `module.name(args)`
+    fn module_member(&mut self, module: &Module, member: &Expr) -> ExprResult {
+        let (name, call) = match member {
+            Expr::Var { name } => (name, None),
+            Expr::Call { callee, arguments, keywords } => match &**callee {
+                Expr::Var { name } => (name, Some((arguments, keywords))),
+                _ => return Err(InterpreterError::InvalidModuleMember { module: module.name().to_string() }),
+            },
+            _ => return Err(InterpreterError::InvalidModuleMember { module: module.name().to_string() }),
+        };
+
+        let value = match module.get(&name.lexeme) {
+            Some(value) => value,
+            None => return Err(InterpreterError::NotExported {
+                module: module.name().to_string(),
+                name: name.lexeme.clone(),
+            }),
+        };
+
+        let (arguments, keywords) = match call {
+            Some(call) => call,
+            None => return Ok(value),
+        };
+
+        let mut args = Vec::new();
+        for argument in arguments {
+            args.push(self.evaluate(argument)?);
+        }
+        let mut keyword_args = Vec::new();
+        for (keyword, argument) in keywords {
+            keyword_args.push((keyword.lexeme.clone(), self.evaluate(argument)?));
+        }
+
+        return self.call_value_with(value, args, keyword_args);
     }
 
-    fn stringify(&self, object: LiteralType) -> String {
-        return match object {
-            LiteralType::Num(n) => {
-                let mut text = n.to_string();
-                if text.ends_with(".0") {
-                    text.truncate(text.len() - 2);
+    /// Calls the method `call` on `value`. When the value is held by `variable`, the variable is
+    /// read again after the arguments are evaluated, and a list method that changes the list
+    /// writes it back. Otherwise the method works on a temporary value, like the result of a call
+    fn call_method(&mut self, value: Value, variable: Option<&Token>, call: &Expr) -> ExprResult {
+        if let Value::Module(module) = &value {
+            return self.module_member(module, call);
+        }
+
+        if let Expr::Call { callee, arguments, keywords } = call {
+            if let Expr::Var { name } = &**callee {
+                // Methods are only called with positional arguments
+                if let Some((keyword, _)) = keywords.first() {
+                    return Err(InterpreterError::UnknownKeywordArgument {
+                        function: name.lexeme.clone(),
+                        name: keyword.lexeme.clone(),
+                    });
                 }
-                text
+
+                let mut args: Vec<Value> = Vec::new();
+
+                for argument in arguments {
+                    let arg = self.evaluate(argument)?;
+                    args.push(arg);
+                }
+
+                // The arguments can change the variable, so it is read again after them
+                let list = match variable {
+                    Some(object) => self.environment.borrow().get(object.clone())?,
+                    None => value,
+                };
+                let mut result_value: Option<Value> = None;
+                let new_list;
+
+                if let Value::Generator(generator) = &list {
+                    return self.generator_method(generator, name.lexeme.as_str(), args);
+                }
+
+                if let Value::Range(range) = &list {
+                    if !args.is_empty() {
+                        return Err(InterpreterError::ArgsDifferFromArity {
+                            args: args.len(),
+                            arity: 0,
+                        });
+                    }
+                    return match name.lexeme.as_str() {
+                        "toList" => Ok(Value::List(range.to_list()?)),
+                        "len" => Ok(Value::from(range.len()?)),
+                        _ => Err(InterpreterError::InvalidListMethod),
+                    };
+                }
+
+                if let Value::List(mut list) = list {
+                    if let (true, Some(object)) = (List::mutates(&name.lexeme), variable) {
+                        self.check_not_iterating(object)?;
+                    }
+                    let original = list.clone();
+
+                    new_list = match name.lexeme.as_str() {
+                        "push" => list.push(args)?,
+                        "pop" => {
+                            let temp = list.pop();
+                            if temp.0.is_some() {
+                                result_value = temp.0;
+                            }
+                            temp.1
+                        },
+                        "remove" => {
+                            let temp = list.remove(args)?;
+                            result_value = Some(temp.0);
+                            temp.1
+                        },
+                        "insertAt" => list.insert_at(args)?,
+                        "index" => return Ok(Value::from(list.index(args)?)),
+                        "len" => return Ok(Value::from(list.len())),
+                        "sort" => list.sort(self, args)?,
+                        "sorted" => return Ok(Value::List(list.sorted(self, args)?)),
+                        "map" => return Ok(Value::List(list.map(self, args)?)),
+                        "filter" => return Ok(Value::List(list.filter(self, args)?)),
+                        "reduce" => return list.reduce(self, args),
+                        "forEach" => {
+                            list.for_each(self, args)?;
+                            return Ok(Value::Literal(LiteralType::Null));
+                        },
+                        "any" => return Ok(Value::from(list.any(self, args)?)),
+                        "all" => return Ok(Value::from(list.all(self, args)?)),
+                        "find" => return list.find(self, args),
+                        "findIndex" => {
+                            let index = match list.find_index(self, args, "findIndex")? {
+                                Some(i) => i as i64,
+                                None => -1,
+                            };
+                            return Ok(Value::from(index));
+                        },
+                        "flatMap" => return Ok(Value::List(list.flat_map(self, args)?)),
+                        "sortBy" => return Ok(Value::List(list.sort_by(self, args)?)),
+                        "reverse" => list.reverse(args)?,
+                        "contains" => return Ok(Value::from(list.contains(args)?)),
+                        "extend" => list.extend(args)?,
+                        "concat" => return Ok(Value::List(list.concat(args)?)),
+                        "join" => return Ok(Value::from(list.join(args)?)),
+                        "count" => return Ok(Value::from(list.count(args)?)),
+                        "clear" => list.clear(args)?,
+                        "copy" => return Ok(Value::List(list.copy(args)?)),
+                        "min" => return list.min(args),
+                        "max" => return list.max(args),
+                        "sum" => return Ok(list.sum(args)?.into_value()),
+                        "unique" => return Ok(Value::List(list.unique(args)?)),
+                        "flatten" => return Ok(Value::List(list.flatten(args)?)),
+                        _ => return Err(InterpreterError::InvalidListMethod)
+                    };
+
+                    // A callback, like a comparator for `sort`, can reassign the variable,
+                    // so it is only written back when the method changed the list
+                    if let (true, Some(object)) = (*new_list != original, variable) {
+                        self.environment.borrow_mut().assign(object.clone(), Value::List(new_list.clone()))?;
+                    }
+                    if let Some(v) = result_value {
+                        return Ok(v);
+                    }
+                }
+            }
+        }
+
+        return Ok(Value::Literal(LiteralType::Null));
+    }
+
+    /// Returns a list of the items at each index in `indices`, for expressions like `items[1..3]`
+    fn select_range(&self, value: Value, indices: &Range) -> ExprResult {
+        let mut items = Vec::new();
+
+        for index in (0..).map_while(|i| indices.item(i)) {
+            let index = match Number::from_value(&index) {
+                Some(n) if n.to_f64().fract() == 0.0 && n.to_f64() >= 0.0 => n.to_f64(),
+                _ => return Err(InterpreterError::IndexOutOfRange),
+            };
+
+            let item = match &value {
+                Value::List(list) => list.values.get(index as usize).cloned(),
+                Value::Range(range) => range.item(index as usize),
+                _ => return Err(InterpreterError::ValueWasNotAList),
+            };
+
+            match item {
+                Some(v) => items.push(v),
+                None => return Err(InterpreterError::IndexOutOfRange),
+            }
+        }
+
+        return Ok(Value::List(List::new(items)));
+    }
+
+    /// Calls one of the methods of a generator. `map`, `filter` and `take` return new generators
+    /// without running this one
+    fn generator_method(&mut self, generator: &Generator, method: &str, args: Vec<Value>) -> ExprResult {
+        let arity = match method {
+            "next" | "toList" => 0,
+            _ => 1,
+        };
+        if args.len() != arity {
+            return Err(InterpreterError::ArgsDifferFromArity { args: args.len(), arity });
+        }
+
+        match method {
+            "next" => return Ok(Value::from(generator.resume(self)?)),
+            "toList" => {
+                let mut values = Vec::new();
+                while let Some(value) = generator.resume(self)? {
+                    values.push(value);
+                }
+                return Ok(Value::List(List::new(values)));
+            }
+            "map" | "filter" => {
+                if !matches!(args[0], Value::Function(_) | Value::NativeFunction(_)) {
+                    return Err(InterpreterError::ExpectedCallback { method: method.to_string() });
+                }
+                if method == "map" {
+                    return Ok(Value::Generator(generator.map(args[0].clone())));
+                }
+                return Ok(Value::Generator(generator.filter(args[0].clone())));
             }
-            LiteralType::Str(s) => s,
-            LiteralType::True => "true".to_string(),
-            LiteralType::False => "false".to_string(),
-            LiteralType::Null => "null".to_string(),
+            "take" => return Ok(Value::Generator(generator.take(to_index(&args[0])?))),
+            _ => return Err(InterpreterError::InvalidGeneratorMethod { method: method.to_string() }),
+        }
+    }
+
+    /// Lists are copied when they are iterated over, so modifying the list inside a `for in` loop
+    /// would have no effect on the items being looped over. Instead, the variable is locked until
+    /// the loop ends, and modifying it is an error. Returns the lock for a loop in `environment`
+    /// over `iterable`, or `None` if it does not loop over a list variable
+    pub fn iteration_lock(environment: &Env, iterable: &Expr, value: &Value) -> Option<(Env, String)> {
+        if let (Expr::Var { name: list }, Value::List(_)) = (iterable, value) {
+            let env = Environment::resolve(environment, &list.lexeme)?;
+            return Some((env, list.lexeme.clone()));
         }
+        return None;
+    }
+
+    /// Returns an error if the list variable `name` is being looped over by a `for in` loop
+    fn check_not_iterating(&self, name: &Token) -> Result<(), InterpreterError> {
+        if let Some(env) = Environment::resolve(&self.environment, &name.lexeme) {
+            let locked = self.iterating
+                .iter()
+                .any(|(e, n)| Rc::ptr_eq(e, &env) && *n == name.lexeme);
+
+            if locked {
+                return Err(InterpreterError::ListModifiedDuringIteration {
+                    name: name.lexeme.clone(),
+                });
+            }
+        }
+        return Ok(());
+    }
+
+    /// Returns an error if `divisor` is zero, unless checked division has been turned off
+    fn check_divisor(&self, divisor: &Value) -> Result<(), InterpreterError> {
+        let is_zero = Number::from_value(divisor).is_some_and(|n| n.is_zero());
+        if self.options.checked_division && is_zero {
+            return Err(InterpreterError::DivisionByZero);
+        }
+        return Ok(());
+    }
+
+    /// Works out which variable or list element an assignment target refers to, evaluating the
+    /// index of a list element
+    fn resolve_place(&mut self, target: &Expr) -> Result<Place, InterpreterError> {
+        match target {
+            Expr::Var { name } => return Ok(Place::Var(name.clone())),
+            Expr::Splice { list, is_splice: false, start: Some(index), end: None, optional: false } => {
+                let index = to_index(&self.evaluate(index)?)?;
+
+                let len = match self.environment.borrow().get(list.clone())? {
+                    Value::List(values) => values.len(),
+                    _ => return Err(InterpreterError::ValueWasNotAList),
+                };
+                if index >= len {
+                    return Err(InterpreterError::IndexOutOfRange);
+                }
+
+                return Ok(Place::Index(list.clone(), index));
+            }
+            _ => return Err(InterpreterError::InvalidAssignmentTarget { target: target.clone() }),
+        }
+    }
+
+    fn read_place(&self, place: &Place) -> ExprResult {
+        match place {
+            Place::Var(name) => return self.environment.borrow().get(name.clone()),
+            Place::Index(list, index) => match self.environment.borrow().get(list.clone())? {
+                Value::List(values) => match values.values.get(*index) {
+                    Some(v) => return Ok(v.clone()),
+                    None => return Err(InterpreterError::IndexOutOfRange),
+                },
+                _ => return Err(InterpreterError::ValueWasNotAList),
+            },
+        }
+    }
+
+    /// Assigns a value to a variable or list element, returning the value
+    fn write_place(&mut self, place: Place, value: Value) -> ExprResult {
+        match place {
+            Place::Var(name) => return self.environment.borrow_mut().assign(name, value),
+            Place::Index(list, index) => {
+                self.check_not_iterating(&list)?;
+
+                let mut values = match self.environment.borrow().get(list.clone())? {
+                    Value::List(values) => values,
+                    _ => return Err(InterpreterError::ValueWasNotAList),
+                };
+                match values.values.get_mut(index) {
+                    Some(v) => *v = value.clone(),
+                    None => return Err(InterpreterError::IndexOutOfRange),
+                }
+
+                self.environment.borrow_mut().assign(list, Value::List(values))?;
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Applies a binary operator to two values that have already been evaluated
+    fn binary_op(&mut self, left: Value, operator: TokenType, right: Value) -> ExprResult {
+        match operator {
+            TokenType::Greater => {
+                comparison!( > ; left ; right);
+                return Err(InterpreterError::ExpectedNumber);
+            }
+            TokenType::GreaterEqual => {
+                comparison!( >= ; left ; right);
+                return Err(InterpreterError::ExpectedNumber);
+            }
+            TokenType::Less => {
+                comparison!( < ; left ; right);
+                return Err(InterpreterError::ExpectedNumber);
+            }
+            TokenType::LessEqual => {
+                comparison!( <= ; left ; right);
+                return Err(InterpreterError::ExpectedNumber);
+            }
+            TokenType::BangEqual => {
+                if !self.is_equal(&left, &right) {
+                    return Ok(Value::Literal(LiteralType::True));
+                }
+                return Ok(Value::Literal(LiteralType::False));
+            }
+            TokenType::EqualEqual => {
+                if self.is_equal(&left, &right) {
+                    return Ok(Value::Literal(LiteralType::True));
+                }
+                return Ok(Value::Literal(LiteralType::False));
+            }
+            TokenType::Plus => {
+                arithmetic!( add ; left ; right );
+                return Err(InterpreterError::ExpectedNumber);
+            }
+            TokenType::Minus => {
+                arithmetic!( sub ; left ; right );
+                return Err(InterpreterError::ExpectedNumber);
+            }
+            TokenType::FSlash => {
+                self.check_divisor(&right)?;
+                arithmetic!( div ; left ; right );
+                return Err(InterpreterError::ExpectedNumber);
+            }
+            TokenType::Asterisk => {
+                arithmetic!( mul ; left ; right );
+                return Err(InterpreterError::ExpectedNumber);
+            }
+            TokenType::Percent => {
+                self.check_divisor(&right)?;
+                arithmetic!( rem ; left ; right );
+                return Err(InterpreterError::ExpectedNumber);
+            }
+            TokenType::FSlashFSlash => {
+                self.check_divisor(&right)?;
+                arithmetic!( floor_div ; left ; right );
+                return Err(InterpreterError::ExpectedNumber);
+            }
+            TokenType::AsteriskAsterisk => {
+                if let (Some(ln), Some(rn)) = (Number::from_value(&left), Number::from_value(&right)) {
+                    return Ok(ln.pow(&rn)?.into_value());
+                }
+                return Err(InterpreterError::ExpectedNumber);
+            }
+            TokenType::Ampersand => {
+                bitwise!( & ; left ; right);
+                return Err(InterpreterError::ExpectedNumber);
+            }
+            TokenType::Pipe => {
+                bitwise!( | ; left ; right);
+                return Err(InterpreterError::ExpectedNumber);
+            }
+            TokenType::Caret => {
+                bitwise!( ^ ; left ; right);
+                return Err(InterpreterError::ExpectedNumber);
+            }
+            TokenType::LessLess | TokenType::GreaterGreater => {
+                if let (Some(ln), Some(rn)) = (Number::from_value(&left), Number::from_value(&right)) {
+                    let value = to_integer(&ln)?;
+                    let amount = to_integer(&rn)?;
+                    if !(0..64).contains(&amount) {
+                        return Err(InterpreterError::InvalidShiftAmount { amount });
+                    }
+
+                    let result = if operator == TokenType::LessLess {
+                        value << amount
+                    } else {
+                        value >> amount
+                    };
+                    return Ok(Value::Literal(LiteralType::Int(result)));
+                }
+                return Err(InterpreterError::ExpectedNumber);
+            }
+            _ => return Err(InterpreterError::ExpectedValidBinaryOperator),
+        }
+    }
+
+    /// Whether a condition holds, following the rules in `Value::is_truthy`
+    pub fn is_truthy(&mut self, object: &Value) -> Result<bool, InterpreterError> {
+        return Ok(object.is_truthy());
+    }
+
+    fn is_equal(&mut self, a: &Value, b: &Value) -> bool {
+        return *a == *b;
+    }
+
+}
+
+/// The names declared by an `export` statement, which a module makes available to its importers
+fn exported_names(stmt: &Stmt) -> Vec<String> {
+    let declaration = match stmt {
+        Stmt::Export { declaration } => declaration,
+        _ => return Vec::new(),
+    };
+
+    match &**declaration {
+        Stmt::Var { name, .. } | Stmt::Function { name, .. } => return vec![name.lexeme.clone()],
+        Stmt::Destructure { pattern, .. } => {
+            return pattern.bindings().into_iter().map(|name| name.lexeme).collect();
+        }
+        _ => return Vec::new(),
     }
 }
+
+/// Converts a number to an `i64` for the bitwise operators, which only work on whole numbers. A
+/// float is accepted if it is whole, but a `BigInt` is too large
+fn to_integer(n: &Number) -> Result<i64, InterpreterError> {
+    match n {
+        Number::Int(n) => return Ok(*n),
+        Number::Float(f) if f.fract() == 0.0 && *f >= i64::MIN as f64 && *f < i64::MAX as f64 => {
+            return Ok(*f as i64);
+        }
+        _ => return Err(InterpreterError::ExpectedInteger { value: n.to_f64() }),
+    }
+}
+
+/// Converts an index into a `usize`. Indices have to be ints, and negative ones are out of range
+fn to_index(value: &Value) -> Result<usize, InterpreterError> {
+    match value {
+        Value::Literal(LiteralType::Int(n)) => {
+            return usize::try_from(*n).map_err(|_| InterpreterError::IndexOutOfRange);
+        }
+        Value::Literal(LiteralType::BigInt(_)) => return Err(InterpreterError::IndexOutOfRange),
+        _ => return Err(InterpreterError::ExpectedIndexToBeAnInt),
+    }
+}
+
 impl expr::ExprVisitor<ExprResult> for Interpreter {
     fn visit_alteration_expr(&mut self, expr: &Expr) -> ExprResult {
         match expr {
-            Expr::Alteration { name, alteration_type } => {
-                let curr_value = self.environment.borrow().get(name.clone())?;
+            Expr::Alteration { target, alteration_type, prefix } => {
+                let place = self.resolve_place(target)?;
+                let old = match Number::from_value(&self.read_place(&place)?) {
+                    Some(n) => n,
+                    None => return Err(InterpreterError::ExpectedNumber),
+                };
 
-                match alteration_type {
-                    TokenType::Incr => {
-                        alteration!( self ;  + ; name ; curr_value);
-                    }
-                    TokenType::Decr => {
-                        alteration!( self ; - ; name ; curr_value);
-                    }
+                let new = match alteration_type {
+                    TokenType::Incr => old.add(&Number::Int(1)),
+                    TokenType::Decr => old.sub(&Number::Int(1)),
                     _ => return Err(InterpreterError::ExpectedAlterationToken),
-                }
+                };
+                self.write_place(place, new.clone().into_value())?;
+
+                return Ok(if *prefix { new.into_value() } else { old.into_value() });
             }
             _ => return Err(InterpreterError::DifferentExpression {
                 expr: expr.clone(),
@@ -162,6 +856,7 @@ impl expr::ExprVisitor<ExprResult> for Interpreter {
         match expr {
             Expr::Assign { name, value } => {
                 let value = self.evaluate(value)?;
+                self.line = name.line;
 
                 return self.environment
                     .borrow_mut()
@@ -174,59 +869,34 @@ impl expr::ExprVisitor<ExprResult> for Interpreter {
         }
     }
 
+    fn visit_destructureassign_expr(&mut self, expr: &Expr) -> ExprResult {
+        match expr {
+            Expr::DestructureAssign { pattern, value } => {
+                let value = self.evaluate(value)?;
+
+                let mut bindings = Vec::new();
+                pattern.destructure(&value, "value", &mut bindings)?;
+                for (name, item) in bindings {
+                    self.write_place(Place::Var(name), item)?;
+                }
+
+                return Ok(value);
+            }
+            _ => return Err(InterpreterError::DifferentExpression {
+                expr: expr.clone(),
+                expected: "destructure assign".to_string(),
+            }),
+        }
+    }
+
     fn visit_binary_expr(&mut self, expr: &Expr) -> ExprResult {
         match expr {
             Expr::Binary { left, operator, right } => {
-                let left = self.evaluate(&left)?;
-                let right = self.evaluate(&right)?;
+                let left = self.evaluate(left)?;
+                let right = self.evaluate(right)?;
+                self.line = operator.line;
 
-                match operator.token_type {
-                    TokenType::Greater => {
-                        comparison!( > ; left ; right);
-                        return Err(InterpreterError::ExpectedNumber);
-                    }
-                    TokenType::GreaterEqual => {
-                        comparison!( >= ; left ; right);
-                        return Err(InterpreterError::ExpectedNumber);
-                    }
-                    TokenType::Less => {
-                        comparison!( < ; left ; right);
-                        return Err(InterpreterError::ExpectedNumber);
-                    }
-                    TokenType::LessEqual => {
-                        comparison!( <= ; left ; right);
-                        return Err(InterpreterError::ExpectedNumber);
-                    }
-                    TokenType::BangEqual => {
-                        if !self.is_equal(&left, &right) {
-                            return Ok(Value::Literal(LiteralType::True));
-                        }
-                        return Ok(Value::Literal(LiteralType::False));
-                    }
-                    TokenType::EqualEqual => {
-                        if self.is_equal(&left, &right) {
-                            return Ok(Value::Literal(LiteralType::True));
-                        }
-                        return Ok(Value::Literal(LiteralType::False));
-                    }
-                    TokenType::Plus => {
-                        arithmetic!( + ; left ; right );
-                        return Err(InterpreterError::ExpectedNumber);
-                    }
-                    TokenType::Minus => {
-                        arithmetic!( - ; left ; right);
-                        return Err(InterpreterError::ExpectedNumber);
-                    }
-                    TokenType::FSlash => {
-                        arithmetic!( / ; left ; right);
-                        return Err(InterpreterError::ExpectedNumber);
-                    }
-                    TokenType::Asterisk => {
-                        arithmetic!( * ; left ; right);
-                        return Err(InterpreterError::ExpectedNumber);
-                    }
-                    _ => return Err(InterpreterError::ExpectedValidBinaryOperator),
-                }
+                return self.binary_op(left, operator.token_type, right);
             }
             _ => return Err(InterpreterError::DifferentExpression {
                 expr: expr.clone(),
@@ -237,7 +907,7 @@ impl expr::ExprVisitor<ExprResult> for Interpreter {
 
     fn visit_call_expr(&mut self, expr: &Expr) -> ExprResult {
         match expr {
-            Expr::Call { callee, arguments } => {
+            Expr::Call { callee, arguments, keywords } => {
                 let callee = self.evaluate(callee)?;
 
                 let mut args: Vec<Value> = Vec::new();
@@ -247,27 +917,13 @@ impl expr::ExprVisitor<ExprResult> for Interpreter {
                     args.push(arg);
                 }
 
-                match callee {
-                    Value::Function(f) => {
-                        if args.len() != f.arity {
-                            return Err(InterpreterError::ArgsDifferFromArity {
-                                args: args.len(),
-                                arity: f.arity,
-                            });
-                        }
-                        return f.call(self, args);
-                    }
-                    Value::NativeFunction(nf) => {
-                        if args.len() != nf.arity {
-                            return Err(InterpreterError::ArgsDifferFromArity {
-                                args: args.len(),
-                                arity: nf.arity,
-                            });
-                        }
-                        return nf.call(self, args);
-                    }
-                    _ => return Err(InterpreterError::ExpectedFunctionOrClass),
+                let mut keyword_args: KeywordArgs = Vec::new();
+                for (name, argument) in keywords {
+                    let arg = self.evaluate(argument)?;
+                    keyword_args.push((name.lexeme.clone(), arg));
                 }
+
+                return self.call_value_with(callee, args, keyword_args);
             }
             _ => return Err(InterpreterError::DifferentExpression {
                 expr: expr.clone(),
@@ -276,6 +932,48 @@ impl expr::ExprVisitor<ExprResult> for Interpreter {
         }
     }
 
+    fn visit_compoundassign_expr(&mut self, expr: &Expr) -> ExprResult {
+        match expr {
+            Expr::CompoundAssign { target, operator, value } => {
+                let place = self.resolve_place(target)?;
+                let current = self.read_place(&place)?;
+                let value = self.evaluate(value)?;
+
+                let binary_operator = match operator.token_type {
+                    TokenType::PlusEqual => TokenType::Plus,
+                    TokenType::MinusEqual => TokenType::Minus,
+                    TokenType::AsteriskEqual => TokenType::Asterisk,
+                    TokenType::FSlashEqual => TokenType::FSlash,
+                    TokenType::PercentEqual => TokenType::Percent,
+                    _ => return Err(InterpreterError::ExpectedValidBinaryOperator),
+                };
+                let result = self.binary_op(current, binary_operator, value)?;
+
+                return self.write_place(place, result);
+            }
+            _ => return Err(InterpreterError::DifferentExpression {
+                expr: expr.clone(),
+                expected: "compound assign".to_string(),
+            }),
+        }
+    }
+
+    fn visit_conditional_expr(&mut self, expr: &Expr) -> ExprResult {
+        match expr {
+            Expr::Conditional { condition, then_branch, else_branch } => {
+                let condition = self.evaluate(condition)?;
+                if self.is_truthy(&condition)? {
+                    return self.evaluate(then_branch);
+                }
+                return self.evaluate(else_branch);
+            }
+            _ => return Err(InterpreterError::DifferentExpression {
+                expr: expr.clone(),
+                expected: "conditional".to_string(),
+            }),
+        }
+    }
+
     fn visit_grouping_expr(&mut self, expr: &Expr) -> ExprResult {
         match expr {
             Expr::Grouping { expression } => return self.evaluate(expression),
@@ -286,6 +984,47 @@ impl expr::ExprVisitor<ExprResult> for Interpreter {
         }
     }
 
+    fn visit_indexassign_expr(&mut self, expr: &Expr) -> ExprResult {
+        match expr {
+            Expr::IndexAssign { list, index, value } => {
+                let target = Expr::Splice {
+                    list: list.clone(),
+                    is_splice: false,
+                    start: Some(index.clone()),
+                    end: None,
+                    optional: false,
+                };
+                let place = self.resolve_place(&target)?;
+                let value = self.evaluate(value)?;
+
+                return self.write_place(place, value);
+            }
+            _ => return Err(InterpreterError::DifferentExpression {
+                expr: expr.clone(),
+                expected: "index assign".to_string(),
+            }),
+        }
+    }
+
+    fn visit_lambda_expr(&mut self, expr: &Expr) -> ExprResult {
+        match expr {
+            Expr::Lambda { keyword, params, body } => {
+                let function = Func::lambda(
+                    keyword,
+                    params.clone(),
+                    body.clone(),
+                    self.environment.clone(),
+                    self.modules.current(),
+                );
+                return Ok(Value::Function(function));
+            },
+            _ => return Err(InterpreterError::DifferentExpression {
+                expr: expr.clone(),
+                expected: "lambda".to_string(),
+            }),
+        }
+    }
+
     fn visit_list_expr(&mut self, expr: &Expr) -> ExprResult {
         match expr {
             Expr::List { items } => {
@@ -304,51 +1043,16 @@ impl expr::ExprVisitor<ExprResult> for Interpreter {
 
     fn visit_listmethodcall_expr(&mut self, expr: &Expr) -> ExprResult {
         match expr {
-            Expr::ListMethodCall { object, call } => {
-                if let Expr::Call { callee, arguments } = &**call {
-                    if let Expr::Var { name } = &**callee {
-                        let mut args: Vec<Value> = Vec::new();
-
-                        for argument in arguments {
-                            let arg = self.evaluate(argument)?;
-                            args.push(arg);
-                        }
-
-                        let list = self.environment.borrow().get(object.clone())?;
-                        let mut result_value: Option<Value> = None;
-                        let new_list;
-
-                        if let Value::List(mut list) = list {
-                            new_list = match name.lexeme.as_str() {
-                                "push" => list.push(args)?,
-                                "pop" => {
-                                    let temp = list.pop();
-                                    if temp.0.is_some() {
-                                        result_value = temp.0;
-                                    }
-                                    temp.1
-                                },
-                                "remove" => {
-                                    let temp = list.remove(args)?;
-                                    result_value = Some(temp.0);
-                                    temp.1
-                                },
-                                "insertAt" => list.insert_at(args)?,
-                                "index" => return Ok(Value::Literal(LiteralType::Num(list.index(args)? as f64))),
-                                "len" => return Ok(Value::Literal(LiteralType::Num(list.len() as f64))),
-                                "sort" => return Ok(Value::List(list.tim_sort()?)),
-                                _ => return Err(InterpreterError::InvalidListMethod)
-                            };
+            Expr::ListMethodCall { object, call, optional } => {
+                self.line = object.line;
 
-                            self.environment.borrow_mut().assign(object.clone(), Value::List(new_list.clone()))?;
-                            if let Some(v) = result_value {
-                                return Ok(v);
-                            }
-                        }
-                    }
+                // Optional calls skip evaluating the arguments as well as the call itself
+                if *optional && self.environment.borrow().get(object.clone())? == Value::Literal(LiteralType::Null) {
+                    return Ok(Value::Literal(LiteralType::Null));
                 }
 
-                return Ok(Value::Literal(LiteralType::Null));
+                let object_value = self.environment.borrow().get(object.clone())?;
+                return self.call_method(object_value, Some(object), call);
             },
             _ => return Err(InterpreterError::DifferentExpression {
                 expr: expr.clone(),
@@ -371,8 +1075,13 @@ impl expr::ExprVisitor<ExprResult> for Interpreter {
         match expr {
             Expr::Logical { left, operator, right } => {
                 let left = self.evaluate(left)?;
+                self.line = operator.line;
 
-                if operator.token_type == TokenType::Or {
+                if operator.token_type == TokenType::QuestionQuestion {
+                    if left != Value::Literal(LiteralType::Null) {
+                        return Ok(left);
+                    }
+                } else if operator.token_type == TokenType::Or {
                     match self.is_truthy(&left) {
                         Ok(v) => {
                             if v {
@@ -401,9 +1110,61 @@ impl expr::ExprVisitor<ExprResult> for Interpreter {
         }
     }
 
+    fn visit_methodcall_expr(&mut self, expr: &Expr) -> ExprResult {
+        match expr {
+            Expr::MethodCall { object, call, optional } => {
+                let value = self.evaluate(object)?;
+                if *optional && value == Value::Literal(LiteralType::Null) {
+                    return Ok(value);
+                }
+
+                return self.call_method(value, None, call);
+            },
+            _ => return Err(InterpreterError::DifferentExpression {
+                expr: expr.clone(),
+                expected: "methodcall".to_string(),
+            }),
+        }
+    }
+
+    fn visit_range_expr(&mut self, expr: &Expr) -> ExprResult {
+        match expr {
+            Expr::Range { start, operator, end, step } => {
+                let mut bounds = vec![self.evaluate(start)?, self.evaluate(end)?];
+                if let Some(step) = step {
+                    bounds.push(self.evaluate(step)?);
+                }
+
+                let mut nums = Vec::new();
+                for bound in bounds {
+                    match Number::from_value(&bound) {
+                        Some(n) => nums.push(n),
+                        None => return Err(InterpreterError::ExpectedNumber),
+                    }
+                }
+
+                let range = Range::new(
+                    nums[0].clone(),
+                    nums[1].clone(),
+                    nums.get(2).cloned(),
+                    operator.token_type == TokenType::DotDotEqual,
+                )?;
+                return Ok(Value::Range(range));
+            }
+            _ => return Err(InterpreterError::DifferentExpression {
+                expr: expr.clone(),
+                expected: "range".to_string(),
+            }),
+        }
+    }
+
     fn visit_splice_expr(&mut self, expr: &Expr) -> ExprResult {
         match expr {
-            Expr::Splice { list, is_splice, start, end } => {
+            Expr::Splice { list, is_splice, start, end, optional } => {
+                if *optional && self.environment.borrow().get(list.clone())? == Value::Literal(LiteralType::Null) {
+                    return Ok(Value::Literal(LiteralType::Null));
+                }
+
                 let mut start_idx_expr: Option<Value> = None;
                 let mut end_idx_expr: Option<Value> = None;
 
@@ -414,52 +1175,61 @@ impl expr::ExprVisitor<ExprResult> for Interpreter {
                     end_idx_expr = Some(self.evaluate(end)?);
                 }
 
+                let value = self.environment.borrow().get(list.clone())?;
+
+                // Indexing with a range selects the item at each index in the range
+                if let Some(Value::Range(indices)) = &start_idx_expr {
+                    if *is_splice {
+                        return Err(InterpreterError::ExpectedIndexToBeAnInt);
+                    }
+                    return self.select_range(value, indices);
+                }
+
                 let mut start_idx: usize = 0;
                 let mut end_idx: Option<usize> = None;
 
-                if let Some(Value::Literal(v)) = start_idx_expr.clone() {
-                    if let LiteralType::Num(num) = v {
-                        start_idx = num as usize;
-                    } else {
-                        return Err(InterpreterError::ExpectedIndexToBeANum);
-                    }
+                if let Some(v) = &start_idx_expr {
+                    start_idx = to_index(v)?;
                 } else if end_idx_expr.is_none() {
-                    return Err(InterpreterError::ExpectedIndexToBeANum)
+                    return Err(InterpreterError::ExpectedIndexToBeAnInt)
                 }
 
-                if let Some(Value::Literal(v)) = end_idx_expr {
-                    if let LiteralType::Num(num) = v {
-                        end_idx = Some(num as usize);
-                    } else {
-                        return Err(InterpreterError::ExpectedIndexToBeANum);
-                    }
-                } else if end_idx.is_some() {
-                    return Err(InterpreterError::ExpectedIndexToBeANum)
+                if let Some(v) = &end_idx_expr {
+                    end_idx = Some(to_index(v)?);
                 }
 
-                let value = self.environment.borrow().get(list.clone())?;
-
-                if let Value::List(list) = value {
-                    if let Some(end_idx) = end_idx {
-                        if end_idx >= list.values.len() {
-                            return Err(InterpreterError::IndexOutOfRange);
-                        }
-                        if start_idx_expr.is_none() {
-                            return Ok(Value::List(List::new(list.values[0..end_idx + 1].to_vec())));
+                let list = match value {
+                    Value::List(list) => list,
+                    Value::Range(range) => {
+                        // Single items are worked out from the range without building a list
+                        if !*is_splice {
+                            return match range.item(start_idx) {
+                                Some(item) => Ok(item),
+                                None => Err(InterpreterError::IndexOutOfRange),
+                            };
                         }
-
-                        return Ok(Value::List(List::new(list.values[start_idx..end_idx + 1].to_vec())));
+                        range.to_list()?
                     }
-                    if start_idx >= list.values.len() {
+                    _ => return Err(InterpreterError::ValueWasNotAList),
+                };
+
+                if let Some(end_idx) = end_idx {
+                    if end_idx >= list.values.len() || start_idx > end_idx {
                         return Err(InterpreterError::IndexOutOfRange);
                     }
-                    if *is_splice {
-                        return Ok(Value::List(List::new(list.values[start_idx..list.values.len()].to_vec())));
+                    if start_idx_expr.is_none() {
+                        return Ok(Value::List(List::new(list.values[0..end_idx + 1].to_vec())));
                     }
-                    return Ok(list.values[start_idx].clone());
-                }
 
-                return Err(InterpreterError::ValueWasNotAList);
+                    return Ok(Value::List(List::new(list.values[start_idx..end_idx + 1].to_vec())));
+                }
+                if start_idx >= list.values.len() {
+                    return Err(InterpreterError::IndexOutOfRange);
+                }
+                if *is_splice {
+                    return Ok(Value::List(List::new(list.values[start_idx..list.values.len()].to_vec())));
+                }
+                return Ok(list.values[start_idx].clone());
             },
             _ => return Err(InterpreterError::DifferentExpression {
                 expr: expr.clone(),
@@ -472,6 +1242,7 @@ impl expr::ExprVisitor<ExprResult> for Interpreter {
         match expr {
             Expr::Unary { operator, right } => {
                 let right = self.evaluate(right)?;
+                self.line = operator.line;
 
                 match operator.token_type {
                     TokenType::Bang => match self.is_truthy(&right) {
@@ -484,11 +1255,17 @@ impl expr::ExprVisitor<ExprResult> for Interpreter {
                         Err(e) => return Err(e),
                     },
                     TokenType::Minus => {
-                        if let Value::Literal(LiteralType::Num(n)) = right {
-                            return Ok(Value::Literal(LiteralType::Num(-n)));
+                        if let Some(n) = Number::from_value(&right) {
+                            return Ok(n.neg().into_value());
                         }
                         return Err(InterpreterError::UnableToNegate)
                     }
+                    TokenType::Tilde => {
+                        if let Some(n) = Number::from_value(&right) {
+                            return Ok(Value::Literal(LiteralType::Int(!to_integer(&n)?)));
+                        }
+                        return Err(InterpreterError::ExpectedNumber)
+                    }
                     _ => return Err(InterpreterError::ExpectedMinus),
                 }
             }
@@ -501,7 +1278,10 @@ impl expr::ExprVisitor<ExprResult> for Interpreter {
 
     fn visit_var_expr(&mut self, expr: &Expr) -> ExprResult {
         match expr {
-            Expr::Var { name } => return self.environment.borrow().get(name.clone()),
+            Expr::Var { name } => {
+                self.line = name.line;
+                return self.environment.borrow().get(name.clone());
+            },
             _ => return Err(InterpreterError::DifferentExpression {
                 expr: expr.clone(),
                 expected: "variable".to_string(),
@@ -533,6 +1313,45 @@ impl stmt::StmtVisitor<StmtResult> for Interpreter {
         }
     }
 
+    fn visit_destructure_stmt(&mut self, stmt: &Stmt) -> StmtResult {
+        match stmt {
+            Stmt::Destructure { pattern, initializer, constant } => {
+                let value = match self.evaluate(initializer) {
+                    Ok(v) => v,
+                    Err(e) => return Err(Err(e)),
+                };
+
+                let mut bindings = Vec::new();
+                if let Err(e) = pattern.destructure(&value, "value", &mut bindings) {
+                    return Err(Err(e));
+                }
+                for (name, item) in bindings {
+                    if *constant {
+                        self.environment.borrow_mut().define_const(name.lexeme, item);
+                    } else {
+                        self.environment.borrow_mut().define(name.lexeme, item);
+                    }
+                }
+
+                return Ok(());
+            }
+            _ => return Err(Err(InterpreterError::DifferentStatement {
+                stmt: stmt.clone(),
+                expected: "destructure".to_string(),
+            })),
+        }
+    }
+
+    fn visit_export_stmt(&mut self, stmt: &Stmt) -> StmtResult {
+        match stmt {
+            Stmt::Export { declaration } => return self.execute(declaration),
+            _ => return Err(Err(InterpreterError::DifferentStatement {
+                stmt: stmt.clone(),
+                expected: "export".to_string(),
+            })),
+        }
+    }
+
     fn visit_expression_stmt(&mut self, stmt: &Stmt) -> StmtResult {
         match stmt {
             Stmt::Expression { expression } => {
@@ -596,10 +1415,41 @@ impl stmt::StmtVisitor<StmtResult> for Interpreter {
         }
     }
 
+    fn visit_forin_stmt(&mut self, stmt: &Stmt) -> StmtResult {
+        match stmt {
+            Stmt::ForIn { name, iterable, body } => {
+                let value = match self.evaluate(iterable) {
+                    Ok(v) => v,
+                    Err(e) => return Err(Err(e)),
+                };
+
+                let lock = Self::iteration_lock(&self.environment, iterable, &value);
+                let mut iter = match ValueIter::new(value) {
+                    Ok(v) => v,
+                    Err(e) => return Err(Err(e)),
+                };
+
+                if let Some(lock) = lock.clone() {
+                    self.iterating.push(lock);
+                }
+                let result = self.for_in(name, &mut iter, body);
+                if lock.is_some() {
+                    self.iterating.pop();
+                }
+
+                return result;
+            }
+            _ => return Err(Err(InterpreterError::DifferentStatement {
+                stmt: stmt.clone(),
+                expected: "for in".to_string(),
+            })),
+        }
+    }
+
     fn visit_function_stmt(&mut self, stmt: &Stmt) -> StmtResult {
         match stmt {
             Stmt::Function { name, .. } => {
-                let function = match Func::new(stmt.clone(), self.environment.clone()) {
+                let function = match Func::new(stmt.clone(), self.environment.clone(), self.modules.current()) {
                     Ok(v) => v,
                     Err(e) => return Err(Err(e)),
                 };
@@ -630,7 +1480,7 @@ impl stmt::StmtVisitor<StmtResult> for Interpreter {
                 };
 
                 if condition_evaluation_result {
-                    match self.execute(&then_branch) {
+                    match self.execute(then_branch) {
                         Ok(_) => {}
                         Err(r) => match r {
                             Ok(v) => return Err(Ok(v)),
@@ -638,7 +1488,7 @@ impl stmt::StmtVisitor<StmtResult> for Interpreter {
                         },
                     };
                 } else if else_branch.is_some() {
-                    match self.execute(&else_branch.as_ref().unwrap()) {
+                    match self.execute(else_branch.as_ref().unwrap()) {
                         Ok(_) => {}
                         Err(r) => return Err(Ok(r)?)
                     };
@@ -653,26 +1503,81 @@ impl stmt::StmtVisitor<StmtResult> for Interpreter {
         }
     }
     
-    fn visit_print_stmt(&mut self, stmt: &Stmt) -> StmtResult {
+    fn visit_import_stmt(&mut self, stmt: &Stmt) -> StmtResult {
         match stmt {
-            Stmt::Print { expression } => {
-                let value = match self.evaluate(expression) {
+            Stmt::Import { keyword, path, alias, names } => {
+                self.line = keyword.line;
+                let module = match self.import_module(path) {
+                    Ok(m) => m,
+                    Err(e) => return Err(Err(e)),
+                };
+
+                if let Some(alias) = alias {
+                    self.environment
+                        .borrow_mut()
+                        .define_const(alias.lexeme.clone(), Value::Module(module.clone()));
+                }
+
+                for name in names {
+                    let value = match module.get(&name.lexeme) {
+                        Some(v) => v,
+                        None => return Err(Err(InterpreterError::NotExported {
+                            module: module.name().to_string(),
+                            name: name.lexeme.clone(),
+                        })),
+                    };
+                    self.environment.borrow_mut().define_const(name.lexeme.clone(), value);
+                }
+
+                return Ok(());
+            }
+            _ => return Err(Err(InterpreterError::DifferentStatement {
+                stmt: stmt.clone(),
+                expected: "import".to_string(),
+            })),
+        }
+    }
+
+    fn visit_match_stmt(&mut self, stmt: &Stmt) -> StmtResult {
+        match stmt {
+            Stmt::Match { value, arms, .. } => {
+                let value = match self.evaluate(value) {
                     Ok(v) => v,
                     Err(e) => return Err(Err(e)),
                 };
-                match value {
-                    Value::Literal(literal) => {
-                        println!("{}", self.stringify(literal.clone()));
-                        self.test_output.push(self.stringify(literal));
-                        return Ok(());
-                    },
-                    Value::List(list) => {
-                        println!("{list}");
-                        self.test_output.push(format!("{list}"));
-                        return Ok(());
-                    },
-                    _ => return Err(Err(InterpreterError::ExpectedToPrintLiteralValue)),
+
+                // When no arm matches, the match does nothing
+                return match self.match_arm(&value, arms, self.environment.clone()) {
+                    Ok(Some((body, environment))) => self.execute_block(vec![body], environment),
+                    Ok(None) => Ok(()),
+                    Err(e) => Err(Err(e)),
+                };
+            }
+            _ => return Err(Err(InterpreterError::DifferentStatement {
+                stmt: stmt.clone(),
+                expected: "match".to_string(),
+            })),
+        }
+    }
+
+    fn visit_print_stmt(&mut self, stmt: &Stmt) -> StmtResult {
+        match stmt {
+            Stmt::Print { keyword, expressions } => {
+                let mut values = Vec::new();
+                for expression in expressions {
+                    match self.evaluate(expression) {
+                        Ok(v) => values.push(v.to_string()),
+                        Err(e) => return Err(Err(e)),
+                    };
+                }
+
+                let line = values.join(" ");
+                if keyword.token_type == TokenType::Eprint {
+                    self.output.write_err(&line);
+                } else {
+                    self.output.write_out(&line);
                 }
+                return Ok(());
             }
             _ => return Err(Err(InterpreterError::DifferentStatement {
                 stmt: stmt.clone(),
@@ -683,7 +1588,8 @@ impl stmt::StmtVisitor<StmtResult> for Interpreter {
 
     fn visit_return_stmt(&mut self, stmt: &Stmt) -> StmtResult {
         match stmt {
-            Stmt::Return { keyword: _, value } => {
+            Stmt::Return { keyword, value } => {
+                self.line = keyword.line;
                 let mut return_value = Value::Literal(LiteralType::Null);
                 if value.is_some() {
                     return_value = match self.evaluate(value.as_ref().unwrap()) {
@@ -702,7 +1608,8 @@ impl stmt::StmtVisitor<StmtResult> for Interpreter {
 
     fn visit_var_stmt(&mut self, stmt: &Stmt) -> StmtResult {
         match stmt {
-            Stmt::Var { name, initializer } => {
+            Stmt::Var { name, initializer, constant } => {
+                self.line = name.line;
                 let mut value = Value::Literal(LiteralType::Null);
                 
                 if let Some(initializer_expr) = initializer {
@@ -711,10 +1618,14 @@ impl stmt::StmtVisitor<StmtResult> for Interpreter {
                         Err(e) => return Err(Err(e)),
                     };
                 }
-                
-                self.environment
-                    .borrow_mut()
-                    .define(name.lexeme.clone(), value);
+
+                if *constant {
+                    self.environment.borrow_mut().define_const(name.lexeme.clone(), value);
+                } else {
+                    self.environment
+                        .borrow_mut()
+                        .define(name.lexeme.clone(), value);
+                }
 
                 return Ok(());
             }
@@ -765,4 +1676,16 @@ impl stmt::StmtVisitor<StmtResult> for Interpreter {
             })),
         }
     }
+
+    fn visit_yield_stmt(&mut self, stmt: &Stmt) -> StmtResult {
+        match stmt {
+            // Functions containing a yield are run by a generator, which handles the yield
+            // itself, so a yield is never executed directly
+            Stmt::Yield { .. } => return Err(Err(InterpreterError::YieldOutsideGenerator)),
+            _ => return Err(Err(InterpreterError::DifferentStatement {
+                stmt: stmt.clone(),
+                expected: "yield".to_string(),
+            })),
+        }
+    }
 }
@@ -0,0 +1,65 @@
+use crate::{
+    error::InterpreterError,
+    generator::Generator,
+    interpreter::Interpreter,
+    range::Range,
+    value::{LiteralType, Value},
+};
+
+/// The iteration protocol used by `for (item in iterable)` loops.
+///
+/// Lists and ranges are iterated item by item, strings character by character and generators
+/// value by value. A function that takes no arguments is treated as a user-defined iterator,
+/// which is called for each item until it returns `null`.
+#[allow(clippy::large_enum_variant)]
+pub enum ValueIter {
+    Items { values: Vec<Value>, index: usize },
+    Chars { chars: Vec<char>, index: usize },
+    Range { range: Range, index: usize },
+    Generator { generator: Generator },
+    Callable { function: Value },
+}
+
+impl ValueIter {
+    pub fn new(value: Value) -> Result<Self, InterpreterError> {
+        return match value {
+            Value::List(list) => Ok(ValueIter::Items { values: list.values, index: 0 }),
+            Value::Literal(LiteralType::Str(s)) => Ok(ValueIter::Chars {
+                chars: s.chars().collect(),
+                index: 0,
+            }),
+            Value::Range(range) => Ok(ValueIter::Range { range, index: 0 }),
+            Value::Generator(generator) => Ok(ValueIter::Generator { generator }),
+            Value::Function(_) | Value::NativeFunction(_) => Ok(ValueIter::Callable { function: value }),
+            _ => Err(InterpreterError::NotIterable { type_name: value.type_name().to_string() }),
+        };
+    }
+
+    /// Returns the next item, or `None` once the iterable has been exhausted
+    pub fn next(&mut self, interpreter: &mut Interpreter) -> Result<Option<Value>, InterpreterError> {
+        match self {
+            ValueIter::Items { values, index } => {
+                let item = values.get(*index).cloned();
+                *index += 1;
+                return Ok(item);
+            }
+            ValueIter::Chars { chars, index } => {
+                let item = chars.get(*index).map(|c| Value::Literal(LiteralType::Str(c.to_string())));
+                *index += 1;
+                return Ok(item);
+            }
+            ValueIter::Range { range, index } => {
+                let item = range.item(*index);
+                *index += 1;
+                return Ok(item);
+            }
+            ValueIter::Generator { generator } => return generator.resume(interpreter),
+            ValueIter::Callable { function } => {
+                return match interpreter.call_value(function.clone(), vec![])? {
+                    Value::Literal(LiteralType::Null) => Ok(None),
+                    item => Ok(Some(item)),
+                };
+            }
+        }
+    }
+}
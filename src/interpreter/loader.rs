@@ -0,0 +1,72 @@
+//! Keeps track of the modules that have been imported by a script.
+//!
+//! The path given to `import` is looked up relative to the directory of the file doing the
+//! importing, or the working directory for the main script, and then in each directory of the
+//! search path in `InterpreterOptions::module_paths`. Each file is only run once, and is cached by
+//! its canonical path so that importing it through a different relative path gives the same
+//! module.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::InterpreterError,
+    module::Module,
+};
+
+#[derive(Debug, Default)]
+pub struct ModuleLoader {
+    cache: HashMap<PathBuf, Module>,
+    loading: Vec<(PathBuf, String)>, // The modules currently being run, innermost last, with the names they were imported by
+}
+
+impl ModuleLoader {
+    /// Finds the file for an import, giving `None` if it does not exist anywhere on the search path
+    pub fn resolve(&self, path: &str, search_paths: &[PathBuf]) -> Option<PathBuf> {
+        let base = match self.loading.last() {
+            Some((current, _)) => current.parent().map(Path::to_path_buf).unwrap_or_default(),
+            None => PathBuf::new(),
+        };
+
+        let candidates = std::iter::once(base.join(path))
+            .chain(search_paths.iter().map(|dir| dir.join(path)));
+        for candidate in candidates {
+            if candidate.is_file() {
+                return candidate.canonicalize().ok();
+            }
+        }
+
+        return None;
+    }
+
+    /// The name of the module being run, or `None` while running the main script
+    pub fn current(&self) -> Option<String> {
+        return self.loading.last().map(|(_, name)| name.clone());
+    }
+
+    pub fn cached(&self, path: &Path) -> Option<Module> {
+        return self.cache.get(path).cloned();
+    }
+
+    /// Marks a module as being run, which fails if it is already being run further up the chain
+    /// of imports, as the two modules would import each other forever
+    pub fn begin(&mut self, path: PathBuf, name: &str) -> Result<(), InterpreterError> {
+        if let Some(start) = self.loading.iter().position(|(p, _)| *p == path) {
+            let mut chain: Vec<&str> = self.loading[start..].iter().map(|(_, n)| n.as_str()).collect();
+            chain.push(name);
+            return Err(InterpreterError::CircularImport { chain: chain.join(" -> ") });
+        }
+
+        self.loading.push((path, name.to_string()));
+        return Ok(());
+    }
+
+    /// Finishes running the innermost module, caching it if it ran successfully
+    pub fn end(&mut self, module: Option<Module>) {
+        if let (Some((path, _)), Some(module)) = (self.loading.pop(), module) {
+            self.cache.insert(path, module);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! The `math` module, which every script can use without importing it, e.g. `math.sqrt(2)`.
+//!
+//! Functions that only make sense for floats, like `sqrt` and the trig functions, accept ints as
+//! well and always give a float. Those that can keep the type of their argument, like `abs`,
+//! `min` and `max`, give back an int for an int, and rounding a float gives an int whenever the
+//! result fits in one.
+
+use std::{
+    cell::RefCell,
+    f64::consts::{E, PI},
+    rc::Rc,
+};
+
+use crate::{
+    callable::NativeFunc,
+    convert::{NativeHandler, Rest},
+    enviromnent::Environment,
+    module::Module,
+    number::Number,
+    value::Value,
+};
+
+/// Builds the `math` module
+pub fn module() -> Module {
+    let members = vec![
+        native("sqrt", &["x"], f64::sqrt),
+        native("pow", &["base", "exponent"], |base: Number, exponent: Number| base.pow(&exponent)),
+        native("abs", &["x"], abs),
+        native("floor", &["x"], |x: Number| to_whole(x, f64::floor)),
+        native("ceil", &["x"], |x: Number| to_whole(x, f64::ceil)),
+        native("round", &["x"], |x: Number| to_whole(x, f64::round)),
+        native("min", &["first", "rest"], |first: Number, rest: Rest<Number>| {
+            rest.0.into_iter().fold(first, |a, b| if b < a { b } else { a })
+        }),
+        native("max", &["first", "rest"], |first: Number, rest: Rest<Number>| {
+            rest.0.into_iter().fold(first, |a, b| if b > a { b } else { a })
+        }),
+        native("sin", &["x"], f64::sin),
+        native("cos", &["x"], f64::cos),
+        native("tan", &["x"], f64::tan),
+        native("asin", &["x"], f64::asin),
+        native("acos", &["x"], f64::acos),
+        native("atan", &["x"], f64::atan),
+        native("atan2", &["y", "x"], f64::atan2),
+        native("exp", &["x"], f64::exp),
+        native("log", &["x", "base"], |x: f64, base: Option<f64>| match base {
+            Some(base) => x.log(base),
+            None => x.ln(),
+        }),
+        native("log2", &["x"], f64::log2),
+        native("log10", &["x"], f64::log10),
+        native("isNaN", &["x"], |x: Number| x.to_f64().is_nan()),
+        native("isFinite", &["x"], |x: Number| x.to_f64().is_finite()),
+        native("isInt", &["x"], |x: Number| !matches!(x, Number::Float(_))),
+        ("pi".to_string(), Value::from(PI)),
+        ("e".to_string(), Value::from(E)),
+    ];
+
+    let environment = Rc::new(RefCell::new(Environment::new(None)));
+    let mut exports = Vec::new();
+    for (name, value) in members {
+        environment.borrow_mut().define_const(name.clone(), value);
+        exports.push(name);
+    }
+
+    return Module::new("math".to_string(), environment, exports);
+}
+
+/// Wraps a closure as a member of the module. Its full name, like `math.sqrt`, is used in errors
+fn native<Args, F>(name: &str, params: &[&str], fun: F) -> (String, Value)
+where
+    F: NativeHandler<Args> + 'static,
+{
+    let function = NativeFunc::from_handler(&format!("math.{name}"), params, fun);
+    return (name.to_string(), Value::NativeFunction(function));
+}
+
+fn abs(x: Number) -> Number {
+    if x < Number::Int(0) {
+        return x.neg();
+    }
+    return x;
+}
+
+/// Rounds a float to a whole number with `round`, giving an int if the result fits in one. Ints
+/// are already whole, so they are left as they are
+fn to_whole(x: Number, round: fn(f64) -> f64) -> Number {
+    match x {
+        Number::Float(f) => {
+            let rounded = round(f);
+            if rounded.is_finite() && rounded >= i64::MIN as f64 && rounded < i64::MAX as f64 {
+                return Number::Int(rounded as i64);
+            }
+            return Number::Float(rounded);
+        }
+        _ => return x,
+    }
+}
@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+
+/// Settings that change how scripts are run, which the host can change through
+/// `Interpreter::options`
+#[derive(Clone, Debug)]
+pub struct InterpreterOptions {
+    /// Whether dividing by zero with `/`, `//` or `%` is a runtime error. When this is turned off,
+    /// division follows the floating point rules instead and can produce `inf` or `NaN`
+    pub checked_division: bool,
+
+    /// The directories that `import` looks in, in order, when a module cannot be found relative
+    /// to the file importing it
+    pub module_paths: Vec<PathBuf>,
+
+    /// The seed for the random number builtins, so that a script gives the same numbers every
+    /// time it runs. When this is `None` the seed comes from the clock. It is read the first time
+    /// a random number is needed, and a call to `seed` in the script replaces it
+    pub seed: Option<u64>,
+}
+
+impl Default for InterpreterOptions {
+    fn default() -> Self {
+        return Self {
+            checked_division: true,
+            module_paths: Vec::new(),
+            seed: None,
+        };
+    }
+}
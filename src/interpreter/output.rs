@@ -0,0 +1,81 @@
+use std::{cell::RefCell, rc::Rc};
+
+/// Where the interpreter writes the output of a script, split into a standard output channel for
+/// `print` and a standard error channel for diagnostics. Each call writes a single line.
+pub trait Output {
+    fn write_out(&mut self, line: &str);
+
+    fn write_err(&mut self, line: &str);
+}
+
+/// Writes to the process's stdout and stderr, which is what the REPL uses
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdOutput;
+
+impl Output for StdOutput {
+    fn write_out(&mut self, line: &str) {
+        println!("{line}");
+    }
+
+    fn write_err(&mut self, line: &str) {
+        eprintln!("{line}");
+    }
+}
+
+/// Stores every line in memory. Clones share the same buffers, so the host can keep a clone to
+/// read the output back after giving the other one to the interpreter.
+#[allow(dead_code)] // Offered to hosts, and used by the tests
+#[derive(Clone, Debug, Default)]
+pub struct BufferOutput {
+    out: Rc<RefCell<Vec<String>>>,
+    err: Rc<RefCell<Vec<String>>>,
+}
+
+#[allow(dead_code)]
+impl BufferOutput {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    pub fn stdout(&self) -> Vec<String> {
+        return self.out.borrow().clone();
+    }
+
+    pub fn stderr(&self) -> Vec<String> {
+        return self.err.borrow().clone();
+    }
+}
+
+impl Output for BufferOutput {
+    fn write_out(&mut self, line: &str) {
+        self.out.borrow_mut().push(line.to_string());
+    }
+
+    fn write_err(&mut self, line: &str) {
+        self.err.borrow_mut().push(line.to_string());
+    }
+}
+
+/// Passes every line to a closure, for hosts that stream output somewhere else
+#[allow(dead_code)] // Offered to hosts
+pub struct CallbackOutput {
+    out: Box<dyn FnMut(&str)>,
+    err: Box<dyn FnMut(&str)>,
+}
+
+#[allow(dead_code)]
+impl CallbackOutput {
+    pub fn new(out: impl FnMut(&str) + 'static, err: impl FnMut(&str) + 'static) -> Self {
+        return Self { out: Box::new(out), err: Box::new(err) };
+    }
+}
+
+impl Output for CallbackOutput {
+    fn write_out(&mut self, line: &str) {
+        (self.out)(line);
+    }
+
+    fn write_err(&mut self, line: &str) {
+        (self.err)(line);
+    }
+}
@@ -0,0 +1,150 @@
+//! Pseudo-random numbers for scripts, through the `random`, `randomInt`, `choice`, `shuffle`,
+//! `sample` and `seed` native functions.
+//!
+//! The generator is SplitMix64, which is small and fast and gives the same sequence for the same
+//! seed on every platform. It is not suitable for anything that needs to be secure. The seed can
+//! be set by the script with `seed(n)`, or by the host through `InterpreterOptions::seed`, and
+//! otherwise comes from the clock.
+
+use std::{
+    collections::VecDeque,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    callable::{Arity, NativeFunc},
+    convert::NativeParam,
+    error::InterpreterError,
+    interpreter::Interpreter,
+    list::List,
+    value::{LiteralType, Value},
+};
+
+#[derive(Clone, Debug)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        return Self { state: seed };
+    }
+
+    /// Seeds the generator from the current time, for when no seed has been given
+    pub fn from_clock() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        return Self::new(nanos);
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        return z ^ (z >> 31);
+    }
+
+    /// A float in `[0, 1)`, made from the top 53 bits so that every value is equally likely
+    pub fn next_f64(&mut self) -> f64 {
+        return (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+    }
+
+    /// A number in `[0, bound)`. Values from the top of the range that would make some results
+    /// more likely than others are thrown away and drawn again
+    pub fn below(&mut self, bound: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let n = self.next_u64();
+            if n < zone {
+                return n % bound;
+            }
+        }
+    }
+
+    /// Shuffles the first `count` items into a random selection of the whole slice, using a
+    /// partial Fisher-Yates shuffle. Shuffling every item gives a random permutation
+    pub fn shuffle<T>(&mut self, items: &mut [T], count: usize) {
+        for i in 0..count.min(items.len()) {
+            let j = i + self.below((items.len() - i) as u64) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+/// Registers the random number functions as builtins
+pub fn register(interpreter: &mut Interpreter) {
+    define(interpreter, "random", &[], |interpreter, _| {
+        return Ok(Value::from(interpreter.random().next_f64()));
+    });
+
+    define(interpreter, "randomInt", &["low", "high"], |interpreter, args| {
+        let low = i64::take(args, "randomInt", "low")?;
+        let high = i64::take(args, "randomInt", "high")?;
+        if low > high {
+            return Err(InterpreterError::InvalidRandomRange { low, high });
+        }
+
+        // Both bounds are included, and the span is worked out with wrapping arithmetic so that
+        // it also covers the full range of ints
+        let span = (high as u64).wrapping_sub(low as u64).wrapping_add(1);
+        let offset = match span {
+            0 => interpreter.random().next_u64(),
+            _ => interpreter.random().below(span),
+        };
+        return Ok(Value::from(low.wrapping_add(offset as i64)));
+    });
+
+    define(interpreter, "choice", &["list"], |interpreter, args| {
+        let items = Vec::<Value>::take(args, "choice", "list")?;
+        if items.is_empty() {
+            return Err(InterpreterError::EmptyList { method: "choice".to_string() });
+        }
+
+        let index = interpreter.random().below(items.len() as u64) as usize;
+        return Ok(items[index].clone());
+    });
+
+    define(interpreter, "shuffle", &["list"], |interpreter, args| {
+        let mut items = Vec::<Value>::take(args, "shuffle", "list")?;
+        let count = items.len();
+        interpreter.random().shuffle(&mut items, count);
+        return Ok(Value::List(List::new(items)));
+    });
+
+    define(interpreter, "sample", &["list", "k"], |interpreter, args| {
+        let mut items = Vec::<Value>::take(args, "sample", "list")?;
+        let k = i64::take(args, "sample", "k")?;
+        let count = match usize::try_from(k) {
+            Ok(count) if count <= items.len() => count,
+            Ok(count) => return Err(InterpreterError::SampleTooLarge { k: count, len: items.len() }),
+            Err(_) => return Err(InterpreterError::NegativeSampleSize { k }),
+        };
+
+        interpreter.random().shuffle(&mut items, count);
+        items.truncate(count);
+        return Ok(Value::List(List::new(items)));
+    });
+
+    define(interpreter, "seed", &["n"], |interpreter, args| {
+        let seed = i64::take(args, "seed", "n")?;
+        interpreter.seed_random(seed as u64);
+        return Ok(Value::Literal(LiteralType::Null));
+    });
+}
+
+/// Defines a native function that needs the interpreter to get at the generator, taking each of
+/// its `params` from the arguments in order
+fn define<F>(interpreter: &mut Interpreter, name: &str, params: &[&str], fun: F)
+where
+    F: Fn(&mut Interpreter, &mut VecDeque<Value>) -> Result<Value, InterpreterError> + 'static,
+{
+    let native = NativeFunc::new(name.to_string(), Arity::Fixed(params.len()), move |interpreter, args| {
+        fun(interpreter, &mut args.into())
+    })
+    .with_params(params.iter().map(|p| p.to_string()).collect());
+
+    interpreter.define_builtin(name, Value::NativeFunction(native));
+}
@@ -0,0 +1,94 @@
+//! The `type` and `repr` builtins, and the `num`, `str`, `bool` and `list` conversions.
+
+use crate::{
+    bigint::BigInt,
+    callable::{Arity, NativeFunc},
+    error::InterpreterError,
+    interpreter::Interpreter,
+    iterator::ValueIter,
+    list::List,
+    value::{LiteralType, Value},
+};
+
+/// Registers the type builtins
+pub fn register(interpreter: &mut Interpreter) {
+    interpreter.register_fn("type", &["value"], |value: Value| value.type_name());
+    interpreter.register_fn("num", &["value"], to_num);
+    interpreter.register_fn("str", &["value"], |value: Value| value.to_string());
+    interpreter.register_fn("repr", &["value"], |value: Value| value.repr());
+    interpreter.register_fn("bool", &["value"], |value: Value| value.is_truthy());
+
+    // Converting a generator runs it to the end, which needs the interpreter
+    let list = NativeFunc::new("list".to_string(), Arity::Fixed(1), |interpreter, args| {
+        to_list(interpreter, args.into_iter().next().unwrap_or(Value::Literal(LiteralType::Null)))
+    })
+    .with_params(vec!["value".to_string()]);
+    interpreter.define_builtin("list", Value::NativeFunction(list));
+}
+
+/// Converts to a number. Numbers are left as they are, `true` and `false` become `1` and `0`, and
+/// strings are parsed as an int if they are whole, or as a float otherwise
+fn to_num(value: Value) -> Result<Value, InterpreterError> {
+    let literal = match &value {
+        Value::Literal(LiteralType::Int(_) | LiteralType::BigInt(_) | LiteralType::Num(_)) => {
+            return Ok(value);
+        }
+        Value::Literal(LiteralType::True) => LiteralType::Int(1),
+        Value::Literal(LiteralType::False) => LiteralType::Int(0),
+        Value::Literal(LiteralType::Str(s)) => match parse_number(s.trim()) {
+            Some(literal) => literal,
+            None => return Err(invalid_conversion(&value, "num")),
+        },
+        _ => return Err(invalid_conversion(&value, "num")),
+    };
+
+    return Ok(Value::Literal(literal));
+}
+
+/// Parses the text of a number. Words like `inf` and `NaN`, which Rust would accept as floats, are
+/// rejected as there are no literals for them in a script
+fn parse_number(text: &str) -> Option<LiteralType> {
+    if !text.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    if let Ok(n) = text.parse::<i64>() {
+        return Some(LiteralType::Int(n));
+    }
+    if let Some(n) = BigInt::parse(text) {
+        return Some(LiteralType::BigInt(n));
+    }
+    return text.parse::<f64>().ok().map(LiteralType::Num);
+}
+
+/// Converts to a list. A string gives its characters, a range or generator gives each of its
+/// items, and a list is left as it is
+fn to_list(interpreter: &mut Interpreter, value: Value) -> Result<Value, InterpreterError> {
+    match &value {
+        Value::List(_) => return Ok(value),
+        Value::Range(range) => return Ok(Value::List(range.to_list()?)),
+        Value::Literal(LiteralType::Str(_)) | Value::Generator(_) => {}
+        _ => return Err(invalid_conversion(&value, "list")),
+    }
+
+    let mut iter = ValueIter::new(value)?;
+    let mut items = Vec::new();
+    while let Some(item) = iter.next(interpreter)? {
+        items.push(item);
+    }
+    return Ok(Value::List(List::new(items)));
+}
+
+/// Describes the value with its type, like `the str "a"`, except for `null` which is its own type
+fn invalid_conversion(value: &Value, target: &str) -> InterpreterError {
+    let shown = match value {
+        Value::Literal(LiteralType::Null) => "null".to_string(),
+        Value::Literal(LiteralType::Str(s)) => format!("the str {s:?}"),
+        _ => format!("the {} {value}", value.type_name()),
+    };
+
+    return InterpreterError::InvalidConversion {
+        value: shown,
+        target: target.to_string(),
+    };
+}
@@ -33,15 +33,15 @@
 //! 1. The lexer reads the source code character by character
 //! 2. It matches the character to a specific token such as identifiers, operators, numbers, etc.
 //! 3. It creates a Token object for each token in the source, which contains information about its
-//! position, contents, and type.
+//!    position, contents, and type.
 //! 4. The lexer continues to process the source code and tokenizes it until it reaches the end of
-//! the source.
+//!    the source.
 //! 5. Finally, it returns the vector of tokens that represent the source code.
 //! 
 //! However, the source code is not just made up of characters that each individually represent
 //! tokens. It also contains:
-//! - Operators: `==` and `++`
-//! - Comments: `// This is a comment`
+//! - Operators: `==`, `++` and `..=`
+//! - Comments: `# This is a comment`
 //! - Identifiers: `foo` and `bar`
 //! - Literals: `"Hello World!"` and `123.456`
 //! - Keywords: `if`, `else` and `def`
@@ -58,6 +58,7 @@
 use std::collections::HashMap;
 
 use crate::{
+    bigint::BigInt,
     error::LexerError,
     keywords,
     token::{Token, TokenType},
@@ -96,8 +97,8 @@ impl Lexer {
         let mut kw: HashMap<String, TokenType> = HashMap::new();
         keywords!(
             kw;
-            And, Class, Def, Else, False, For, If, Null, Or,
-            Print, Return, Super, This, True, Var, While
+            And, As, Class, Const, Def, Else, Eprint, Export, False, For, From, If, Import, In, Match, Null, Or,
+            Print, Return, Super, This, True, Var, While, Yield
         );
 
         return Self {
@@ -118,7 +119,7 @@ impl Lexer {
     /// 
     /// ## Returns
     /// - [`Result<Vec<Token>, LexerError>`]: Either successfully returns the vector of tokens, or
-    /// a `LexerError` where something has led to an error during the scanning process.
+    ///   a `LexerError` where something has led to an error during the scanning process.
     pub fn run(&mut self) -> Result<Vec<Token>, LexerError> {
         while !self.is_at_end() {
 
@@ -173,15 +174,19 @@ impl Lexer {
 
     /// Processes a string token once `"` is found, and repeatedly advances, as long as another `"`
     /// is found or the end of the source code is not reached.
+    ///
+    /// A string can span several lines, and the newlines inside it are counted so that the tokens
+    /// after it have the right line. Only reaching the end of the source without a closing `"`
+    /// leaves it unterminated.
     /// 
     /// ## Returns
     /// [`Result<(), LexerError>`]: Either successfully returns nothing once the string token is
     /// processed and pushed to the tokens vector or returns a [`LexerError`] if an error is
     /// encountered
     fn string(&mut self) -> Result<(), LexerError> {
-        while self.peek()? != '"' && !self.is_at_end() {
+        while !self.is_at_end() && self.peek()? != '"' {
             if self.peek()? == '\n' {
-                return Err(LexerError::UnterminatedString { line: self.line });
+                self.line += 1;
             }
             self.advance()?;
         }
@@ -199,28 +204,120 @@ impl Lexer {
 
     /// Processes numbers when a digit is found, and, similarly to `string()`, it repeatedly 
     /// advances as long as the next character is a digit or is not a decimal point followed by the
-    /// fractional part of the number
+    /// fractional part of the number. Numbers can also be written in hex, binary or octal with a
+    /// `0x`, `0b` or `0o` prefix, can have an exponent, and can use `_` to separate digits
+    ///
+    /// The literal of the token has the separators removed, and radix literals are converted to
+    /// decimal, so the parser only has to handle plain decimal numbers
     /// 
     /// ## Returns
     /// [`Result<(), LexerError>`]
     fn number(&mut self) -> Result<(), LexerError> {
-        while self.is_digit(self.peek()?) {
-            self.advance()?;
+        if self.source[self.start..self.curr] == *"0" && !self.is_at_end() {
+            let radix = match self.peek()? {
+                'x' | 'X' => Some(16),
+                'b' | 'B' => Some(2),
+                'o' | 'O' => Some(8),
+                _ => None,
+            };
+            if let Some(radix) = radix {
+                self.advance()?;
+                return self.radix_number(radix);
+            }
         }
 
-        if self.peek()? == '.' && self.is_digit(self.peek_next()?) {
+        self.digits(10)?;
+
+        // A '.' is only part of the number if it is followed by a digit, so that `1..5` is lexed
+        // as a range
+        let has_fraction = !self.is_at_end()
+            && self.peek()? == '.'
+            && self.is_digit(self.peek_next()?);
+
+        if has_fraction {
             self.advance()?;
+            self.digits(10)?;
+        }
 
-            while self.is_digit(self.peek()?) {
+        let has_exponent = !self.is_at_end() && matches!(self.peek()?, 'e' | 'E');
+        if has_exponent {
+            self.advance()?;
+            if !self.is_at_end() && matches!(self.peek()?, '+' | '-') {
                 self.advance()?;
             }
+            if self.is_at_end() || !self.is_digit(self.peek()?) {
+                return Err(LexerError::MissingDigits {
+                    literal: self.source[self.start..self.curr].to_string(),
+                    line: self.line,
+                });
+            }
+            self.digits(10)?;
         }
 
-        let value = String::from(&self.source[self.start..self.curr]);
-        self.add_string_token(TokenType::Num, value);
+        let value = self.number_text(10)?;
+
+        // Numbers without a fraction or exponent are ints, so `1` and `1.0` are different types
+        let token_type = if has_fraction || has_exponent { TokenType::Num } else { TokenType::Int };
+        self.add_string_token(token_type, value);
         Ok(())
     }
 
+    /// Processes the digits of a hex, binary or octal literal, after its prefix
+    fn radix_number(&mut self, radix: u32) -> Result<(), LexerError> {
+        self.digits(radix)?;
+
+        let text = self.number_text(radix)?;
+        let value = match BigInt::parse_radix(&text[2..], radix) {
+            Some(n) => n.to_string(),
+            None => {
+                return Err(LexerError::MissingDigits { literal: text, line: self.line });
+            }
+        };
+
+        self.add_string_token(TokenType::Int, value);
+        Ok(())
+    }
+
+    /// Advances past any digits in the given base, along with any '_' separators
+    fn digits(&mut self, radix: u32) -> Result<(), LexerError> {
+        while !self.is_at_end() && (self.peek()?.is_digit(radix) || self.peek()? == '_') {
+            self.advance()?;
+        }
+        Ok(())
+    }
+
+    /// Checks that the number that has just been processed is well formed, returning its text
+    /// with the separators removed
+    fn number_text(&mut self, radix: u32) -> Result<String, LexerError> {
+        let text = &self.source[self.start..self.curr];
+
+        // A letter or digit straight after a number means it has a digit that is invalid in its
+        // base, such as the '2' in `0b12`
+        if !self.is_at_end() && self.is_alpha(self.peek()?) {
+            return Err(LexerError::InvalidDigit {
+                c: self.peek()?,
+                literal: text.to_string(),
+                line: self.line,
+            });
+        }
+
+        let chars: Vec<char> = text.chars().collect();
+        for (i, c) in chars.iter().enumerate() {
+            let between_digits = i > 0
+                && chars[i - 1].is_digit(radix)
+                && chars.get(i + 1).is_some_and(|next| next.is_digit(radix));
+
+            if *c == '_' && !between_digits {
+                return Err(LexerError::MisplacedDigitSeparator {
+                    literal: text.to_string(),
+                    line: self.line,
+                });
+            }
+        }
+
+        return Ok(text.replace('_', ""));
+    }
+
     fn identifier(&mut self) -> Result<(), LexerError> {
         while !self.is_at_end() && self.is_alpha(self.peek()?) {
             self.advance()?;
@@ -252,13 +349,58 @@ impl Lexer {
             '[' => token = TokenType::LBrack,
             ']' => token = TokenType::RBrack,
             ',' => token = TokenType::Comma,
-            '.' => token = TokenType::Dot,
+            '.' => {
+                if self.match_token('.') {
+                    if self.match_token('=') {
+                        token = TokenType::DotDotEqual;
+                    } else if self.match_token('.') {
+                        token = TokenType::DotDotDot;
+                    } else {
+                        token = TokenType::DotDot;
+                    }
+                } else {
+                    token = TokenType::Dot;
+                }
+            }
             ';' => token = TokenType::Semicolon,
             ':' => token = TokenType::Colon,
-            '*' => token = TokenType::Asterisk,
+            '?' => {
+                if self.match_token('?') {
+                    token = TokenType::QuestionQuestion;
+                } else if self.peek_next().is_ok_and(|c| !self.is_digit(c)) && self.match_token('.') {
+                    // `c ?.5 : 1` is a conditional whose value is a float, not an optional access
+                    token = TokenType::QuestionDot;
+                } else if self.match_token('[') {
+                    token = TokenType::QuestionLBrack;
+                } else {
+                    token = TokenType::Question;
+                }
+            }
+            '*' => {
+                if self.match_token('*') {
+                    token = TokenType::AsteriskAsterisk;
+                } else if self.match_token('=') {
+                    token = TokenType::AsteriskEqual;
+                } else {
+                    token = TokenType::Asterisk;
+                }
+            }
+            '%' => {
+                if self.match_token('=') {
+                    token = TokenType::PercentEqual;
+                } else {
+                    token = TokenType::Percent;
+                }
+            }
+            '&' => token = TokenType::Ampersand,
+            '|' => token = TokenType::Pipe,
+            '^' => token = TokenType::Caret,
+            '~' => token = TokenType::Tilde,
             '-' => {
                 if self.match_token('-') {
                     token = TokenType::Decr;
+                } else if self.match_token('=') {
+                    token = TokenType::MinusEqual;
                 } else {
                     token = TokenType::Minus;
                 }
@@ -266,6 +408,8 @@ impl Lexer {
             '+' => {
                 if self.match_token('+') {
                     token = TokenType::Incr;
+                } else if self.match_token('=') {
+                    token = TokenType::PlusEqual;
                 } else {
                     token = TokenType::Plus;
                 }
@@ -280,6 +424,8 @@ impl Lexer {
             '=' => {
                 if self.match_token('=') {
                     token = TokenType::EqualEqual;
+                } else if self.match_token('>') {
+                    token = TokenType::Arrow;
                 } else {
                     token = TokenType::Equal;
                 }
@@ -287,6 +433,8 @@ impl Lexer {
             '<' => {
                 if self.match_token('=') {
                     token = TokenType::LessEqual;
+                } else if self.match_token('<') {
+                    token = TokenType::LessLess;
                 } else {
                     token = TokenType::Less;
                 }
@@ -294,6 +442,8 @@ impl Lexer {
             '>' => {
                 if self.match_token('=') {
                     token = TokenType::GreaterEqual;
+                } else if self.match_token('>') {
+                    token = TokenType::GreaterGreater;
                 } else {
                     token = TokenType::Greater;
                 }
@@ -306,12 +456,22 @@ impl Lexer {
                 self.line += 1;
                 return Ok(());
             }
-            ' ' | '\n' | '\t' => return Ok(()),
+            '\n' => {
+                self.line += 1;
+                return Ok(());
+            }
+            ' ' | '\t' => return Ok(()),
+            '#' => {
+                while self.peek()? != '\n' && !self.is_at_end() {
+                    self.advance()?;
+                }
+                return Ok(());
+            }
             '/' => {
                 if self.match_token('/') {
-                    while self.peek()? != '\n' && !self.is_at_end() {
-                        self.advance()?;
-                    }
+                    self.add_token(TokenType::FSlashFSlash);
+                } else if self.match_token('=') {
+                    self.add_token(TokenType::FSlashEqual);
                 } else {
                     self.add_token(TokenType::FSlash);
                 }
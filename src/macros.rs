@@ -1,43 +1,40 @@
 #[macro_export]
-// Carries out arithmetic operations when binary expressions are evaluated
+// Carries out arithmetic operations when binary expressions are evaluated, using the `Number`
+// method with the given name so that ints are promoted to floats or `BigInt`s where needed
 macro_rules! arithmetic {
-    ( $operator:tt ; $num1:expr ; $num2:expr ) => {
-        if let Value::Literal(LiteralType::Num(ln)) = $num1 {
-            if let Value::Literal(LiteralType::Num(rn)) = $num2 {
-                return Ok(Value::Literal(LiteralType::Num(ln $operator rn)));
-            }
+    ( $method:ident ; $num1:expr ; $num2:expr ) => {
+        if let (Some(ln), Some(rn)) = (Number::from_value(&$num1), Number::from_value(&$num2)) {
+            return Ok(ln.$method(&rn).into_value());
         }
     };
 }
 
 #[macro_export]
-// Carries out comparison operations when binary expressions are evaluated
-macro_rules! comparison {
+// Carries out bitwise operations when binary expressions are evaluated, which only work on whole
+// numbers
+macro_rules! bitwise {
     ( $operator:tt ; $num1:expr ; $num2:expr ) => {
-        if let Value::Literal(LiteralType::Num(ln)) = $num1 {
-            if let Value::Literal(LiteralType::Num(rn)) = $num2 {
-                return Ok(
-                    if ln $operator rn {
-                        Value::Literal(LiteralType::True)
-                    } else {
-                        Value::Literal(LiteralType::False)
-                    }
-                );
-            }
+        if let (Some(ln), Some(rn)) = (Number::from_value(&$num1), Number::from_value(&$num2)) {
+            let result = to_integer(&ln)? $operator to_integer(&rn)?;
+            return Ok(Value::Literal(LiteralType::Int(result)));
         }
     };
 }
 
 #[macro_export]
-// Increments or decrements the value in the alteration expression
-macro_rules! alteration {
-    ( $self:ident ; $operator:tt ; $name:expr ; $value:expr ) => {
-        if let Value::Literal(LiteralType::Num(n)) = $value {
-            return $self.environment.borrow_mut().assign(
-                $name.clone(), Value::Literal(LiteralType::Num(n $operator 1.0))
+// Carries out comparison operations when binary expressions are evaluated, where ints and floats
+// can be compared with each other
+macro_rules! comparison {
+    ( $operator:tt ; $num1:expr ; $num2:expr ) => {
+        if let (Some(ln), Some(rn)) = (Number::from_value(&$num1), Number::from_value(&$num2)) {
+            return Ok(
+                if ln $operator rn {
+                    Value::Literal(LiteralType::True)
+                } else {
+                    Value::Literal(LiteralType::False)
+                }
             );
-        };
-        return Err(InterpreterError::ExpectedNumber);
+        }
     };
 }
 
@@ -107,3 +104,35 @@ macro_rules! expr_visitor {
         }
     };
 }
+
+
+#[macro_export]
+// Implements `NativeHandler` for closures taking the given number of convertible parameters
+macro_rules! native_handler {
+    ( $($params:ident),* ) => {
+        #[allow(non_snake_case, unused_mut, unused_variables)]
+        impl<Fun, Ret, $($params),*> NativeHandler<($($params,)*)> for Fun
+        where
+            Fun: Fn($($params),*) -> Ret + 'static,
+            Ret: NativeReturn,
+            $($params: NativeParam,)*
+        {
+            fn kinds() -> Vec<ParamKind> {
+                return vec![$($params::kind()),*];
+            }
+
+            fn invoke(&self, function: &str, params: &[String], args: Vec<Value>) -> Result<Value, InterpreterError> {
+                let mut args: VecDeque<Value> = args.into();
+                let mut names = params.iter();
+                $(
+                    let $params = $params::take(
+                        &mut args,
+                        function,
+                        names.next().map(|n| n.as_str()).unwrap_or("_"),
+                    )?;
+                )*
+                return (self)($($params),*).into_result();
+            }
+        }
+    };
+}
@@ -1,11 +1,42 @@
+// The codebase ends every function with an explicit `return`, and errors carry the tokens and
+// values they are about, which makes `InterpreterError` larger than clippy would like
+#![allow(clippy::needless_return, clippy::result_large_err)]
+
 mod error;
 
 #[path = "./interpreter/environment.rs"]
 mod enviromnent;
 
+#[path = "./interpreter/format.rs"]
+mod format;
+
+#[path = "./interpreter/generator.rs"]
+mod generator;
+
 #[path = "./interpreter/interpreter.rs"]
 mod interpreter;
 
+#[path = "./interpreter/iterator.rs"]
+mod iterator;
+
+#[path = "./interpreter/loader.rs"]
+mod loader;
+
+#[path = "./interpreter/math.rs"]
+mod math;
+
+#[path = "./interpreter/options.rs"]
+mod options;
+
+#[path = "./interpreter/output.rs"]
+mod output;
+
+#[path = "./interpreter/random.rs"]
+mod random;
+
+#[path = "./interpreter/types.rs"]
+mod types;
+
 #[path = "./lexer/lexer.rs"]
 mod lexer;
 
@@ -17,15 +48,33 @@ mod parser;
 #[path = "./semanticanalyser/semanticanalyser.rs"]
 mod semanticanalyser;
 
+#[path = "./values/bigint.rs"]
+mod bigint;
+
 #[path = "./values/callable.rs"]
 mod callable;
 
+#[path = "./values/convert.rs"]
+mod convert;
+
 #[path = "./values/expr.rs"]
 mod expr;
 
 #[path = "./values/list.rs"]
 mod list;
 
+#[path = "./values/module.rs"]
+mod module;
+
+#[path = "./values/number.rs"]
+mod number;
+
+#[path = "./values/pattern.rs"]
+mod pattern;
+
+#[path = "./values/range.rs"]
+mod range;
+
 mod run;
 
 #[path = "./values/stmt.rs"]
@@ -37,9 +86,7 @@ mod token;
 #[path = "./values/value.rs"]
 mod value;
 
-#[path = "./tests/interpreter_tests.rs"]
-mod interpreter_tests;
-
+// Both test modules are declared in tests/mod.rs, so they are only compiled for `cargo test`
 #[cfg(test)]
 mod tests;
 
@@ -49,8 +96,12 @@ use rocket::serde::{Deserialize, Serialize, json::Json};
 use rocket_cors::{AllowedHeaders, AllowedOrigins, Cors, CorsOptions};
 use std::io::Write;
 
-use run::run;
+use interpreter::Interpreter;
+use output::BufferOutput;
+use run::{run, run_in};
 
+// The body of a request to the web server, which is switched off below
+#[allow(dead_code)]
 #[derive(Serialize, Deserialize)]
 struct Message {
     source: String,
@@ -63,7 +114,7 @@ fn repl() -> String {
         print!("> ");
         std::io::stdout().flush().unwrap();
         std::io::stdin().read_line(&mut temp_source).unwrap();
-        if temp_source.trim().eq("run") || temp_source.trim().eq("") {
+        if temp_source.trim().eq("run") || temp_source.trim().is_empty() {
             return source;
         }
         temp_source.push('\n');
@@ -95,9 +146,13 @@ fn _make_cors() -> Cors {
 
 #[post("/runcode", format = "json", data = "<message>")]
 fn _run_code(message: Json<Message>) -> Json<String> {
-    let output = run(message.source.as_str());
+    let output = BufferOutput::new();
+    let mut interpreter = Interpreter::with_output(output.clone());
+    run_in(&mut interpreter, message.source.as_str());
 
-    Json(format!("{:?}", output))
+    let mut lines = output.stdout();
+    lines.extend(output.stderr());
+    Json(format!("{:?}", lines))
 }
 
 
@@ -47,9 +47,12 @@
 //! 1. The parser evaluates the tokens one by one, and starts off by 
 
 use crate::{
+    bigint::BigInt,
     error::ParserError,
     expr::Expr,
-    stmt::Stmt,
+    number::Number,
+    pattern::{MatchArm, Pattern},
+    stmt::{Parameter, Stmt},
     token::{Token, TokenType},
     value::LiteralType,
 };
@@ -57,11 +60,14 @@ use crate::{
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    /// How many enclosing conditionals and splices are waiting for a ':', which decides whether
+    /// a `?[` is an optional index or the start of a conditional
+    colons_expected: usize,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        return Self { tokens, current: 0 };
+        return Self { tokens, current: 0, colons_expected: 0 };
     }
 
     pub fn parse(&mut self) -> Result<Vec<Stmt>, ParserError> {
@@ -78,7 +84,28 @@ impl Parser {
     }
 
     fn declaration(&mut self) -> Result<Stmt, ParserError> {
-        if self.match_token(vec![&TokenType::Def]) {
+        if self.match_token(vec![&TokenType::Export]) {
+            return match self.export_declaration() {
+                Ok(v) => Ok(v),
+                Err(e) => {
+                    self.synchronize();
+                    Err(e)
+                }
+            }
+        }
+        if self.match_token(vec![&TokenType::Import, &TokenType::From]) {
+            return match self.import_declaration() {
+                Ok(v) => Ok(v),
+                Err(e) => {
+                    self.synchronize();
+                    Err(e)
+                }
+            }
+        }
+
+        // 'def' followed by '(' is an anonymous function, which is parsed as an expression
+        if self.check(TokenType::Def) && !self.check_next(TokenType::LParen) {
+            self.advance();
             return match self.function("function") {
                 Ok(v) => Ok(v),
                 Err(e) => {
@@ -86,8 +113,9 @@ impl Parser {
                     Err(e)
                 }
             }
-        } else if self.match_token(vec![&TokenType::Var]) {
-            return match self.var_declaration() {
+        } else if self.match_token(vec![&TokenType::Var, &TokenType::Const]) {
+            let constant = self.previous().token_type == TokenType::Const;
+            return match self.var_declaration(constant) {
                 Ok(v) => Ok(v),
                 Err(e) => {
                     self.synchronize();
@@ -99,8 +127,54 @@ impl Parser {
         }
     }
 
+    /// Parses the declaration after 'export', which must declare a variable, constant or named
+    /// function
+    fn export_declaration(&mut self) -> Result<Stmt, ParserError> {
+        let is_declaration = self.check(TokenType::Var)
+            || self.check(TokenType::Const)
+            || (self.check(TokenType::Def) && !self.check_next(TokenType::LParen));
+        if !is_declaration {
+            return Err(ParserError::ExpectedDeclarationAfterExport {
+                line: self.peek().line,
+            });
+        }
+
+        let declaration = self.declaration()?;
+        return Ok(Stmt::Export { declaration: Box::new(declaration) });
+    }
+
+    /// Parses `import "path" as name;` or `from "path" import a, b;`
+    fn import_declaration(&mut self) -> Result<Stmt, ParserError> {
+        let keyword = self.previous().clone();
+        if !self.check(TokenType::String) {
+            return Err(ParserError::ExpectedModulePath {
+                keyword: keyword.lexeme,
+                line: keyword.line,
+            });
+        }
+        let path = self.advance().clone();
+
+        let mut alias = None;
+        let mut names = Vec::new();
+        if keyword.token_type == TokenType::Import {
+            self.consume(TokenType::As, "ExpectedAsAfterImport")?;
+            alias = Some(self.consume(TokenType::Identifier, "ExpectedAsAfterImport")?);
+        } else {
+            self.consume(TokenType::Import, "ExpectedImportAfterFrom")?;
+            loop {
+                names.push(self.consume(TokenType::Identifier, "ExpectedImportName")?);
+                if !self.match_token(vec![&TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::Semicolon, "ExpectedSemicolonAfterImport")?;
+
+        return Ok(Stmt::Import { keyword, path, alias, names });
+    }
+
     fn function(&mut self, kind: &str) -> Result<Stmt, ParserError> {
-        let name = match self.consume(
+        let name = self.consume(
             TokenType::Identifier,
             format!(
                 "Expected{}Name",
@@ -112,10 +186,7 @@ impl Parser {
                     + &kind[1..]
             )
             .as_str(),
-        ) {
-            Ok(v) => v,
-            Err(e) => return Err(e),
-        };
+        )?;
 
         self.consume(
             TokenType::LParen,
@@ -131,19 +202,54 @@ impl Parser {
             .as_str(),
         )?;
 
-        let mut params: Vec<Token> = Vec::new();
+        let params = self.parameters(&name.lexeme)?;
+
+        self.consume(TokenType::RParen, "ExpectedRParenAfterParameters")?;
+
+        self.consume(TokenType::LBrace, "ExpectedLBraceBeforeFunctionBody")?;
+
+        let body = self.block()?;
+
+        return Ok(Stmt::Function { name, params, body });
+    }
+
+    /// Parses a parameter list like `a, b = 2, ...rest`. Parameters with a default value must come
+    /// after those without one, and a variadic parameter must be last
+    fn parameters(&mut self, name: &str) -> Result<Vec<Parameter>, ParserError> {
+        let mut params: Vec<Parameter> = Vec::new();
         if !self.check(TokenType::RParen) {
             loop {
                 if params.len() >= 255 {
                     let token = self.peek();
                     return Err(ParserError::TooManyParameters {
-                        name: name.lexeme,
+                        name: name.to_string(),
                         line: token.line,
                     });
                 }
 
-                let parameter = self.consume(TokenType::Identifier, "ExpectedParameterName")?;
-                params.push(parameter);
+                if self.match_token(vec![&TokenType::DotDotDot]) {
+                    let name = self.consume(TokenType::Identifier, "ExpectedParameterName")?;
+                    params.push(Parameter { name, default: None, variadic: true });
+
+                    if !self.check(TokenType::RParen) {
+                        return Err(ParserError::VariadicParameterNotLast {
+                            line: self.peek().line,
+                        });
+                    }
+                    break;
+                }
+
+                let name = self.consume(TokenType::Identifier, "ExpectedParameterName")?;
+                let mut default = None;
+                if self.match_token(vec![&TokenType::Equal]) {
+                    default = Some(self.expression()?);
+                } else if params.iter().any(|p| p.default.is_some()) {
+                    return Err(ParserError::RequiredParameterAfterDefault {
+                        name: name.lexeme.clone(),
+                        line: name.line,
+                    });
+                }
+                params.push(Parameter { name, default, variadic: false });
 
                 if !self.match_token(vec![&TokenType::Comma]) {
                     break;
@@ -151,17 +257,129 @@ impl Parser {
             }
         }
 
-        self.consume(TokenType::RParen, "ExpectedRParenAfterParameters")?;
+        return Ok(params);
+    }
 
-        self.consume(TokenType::LBrace, "ExpectedRParenAfterParameters")?;
+    /// Parses an anonymous function declared with `def`, e.g. `def (x) { return x * 2; }`
+    fn lambda(&mut self) -> Result<Expr, ParserError> {
+        let keyword = self.previous().clone();
+
+        self.consume(TokenType::LParen, "ExpectedLParenAfterDef")?;
+        let params = self.parameters("lambda")?;
+        self.consume(TokenType::RParen, "ExpectedRParenAfterParameters")?;
+        self.consume(TokenType::LBrace, "ExpectedLBraceBeforeFunctionBody")?;
 
         let body = self.block()?;
 
-        return Ok(Stmt::Function { name, params, body });
+        return Ok(Expr::Lambda { keyword, params, body });
+    }
+
+    /// Parses an arrow function, e.g. `(x) => x * 2`. The body can either be a single expression,
+    /// which is returned, or a block
+    fn arrow_function(&mut self) -> Result<Expr, ParserError> {
+        self.consume(TokenType::LParen, "ExpectedLParenAfterDef")?;
+        let params = self.parameters("lambda")?;
+        self.consume(TokenType::RParen, "ExpectedRParenAfterParameters")?;
+        let keyword = self.consume(TokenType::Arrow, "ExpectedArrow")?;
+
+        if self.match_token(vec![&TokenType::LBrace]) {
+            let body = self.block()?;
+            return Ok(Expr::Lambda { keyword, params, body });
+        }
+
+        let value = self.expression()?;
+        let body = vec![Stmt::Return { keyword: keyword.clone(), value: Some(value) }];
+
+        return Ok(Expr::Lambda { keyword, params, body });
+    }
+
+    /// Looks ahead from a '(' to see whether it starts the parameter list of an arrow function
+    /// rather than a grouping, by checking for a '=>' after the matching ')'. The parameters can
+    /// contain brackets of their own through default values
+    fn is_arrow_function(&self) -> bool {
+        let mut depth = 0;
+
+        for (i, token) in self.tokens.iter().enumerate().skip(self.current) {
+            match token.token_type {
+                TokenType::LParen => depth += 1,
+                TokenType::RParen => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return self.tokens.get(i + 1).is_some_and(|t| t.token_type == TokenType::Arrow);
+                    }
+                }
+                TokenType::Eof => return false,
+                _ => {}
+            }
+        }
+
+        return false;
+    }
+
+    /// Looks ahead from a `?[` to see whether it is the '?' of a conditional followed by a list,
+    /// like `c ?[1] : [2]`, rather than an optional index. It is a conditional when the matching
+    /// ']' is followed by a ':' that no enclosing conditional or splice is waiting for
+    fn is_conditional_list(&self) -> bool {
+        if self.colons_expected > 0 {
+            return false;
+        }
+
+        let mut depth = 0;
+        for (i, token) in self.tokens.iter().enumerate().skip(self.current) {
+            match token.token_type {
+                TokenType::LBrack | TokenType::QuestionLBrack => depth += 1,
+                TokenType::RBrack => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return self.tokens.get(i + 1).is_some_and(|t| t.token_type == TokenType::Colon);
+                    }
+                }
+                TokenType::Eof => return false,
+                _ => {}
+            }
+        }
+
+        return false;
+    }
+
+    /// Splits the current `?[` token into a '?' and a '['
+    fn split_question_lbrack(&mut self) {
+        let token = self.tokens[self.current].clone();
+        let question = Token::new(TokenType::Question, "?".to_string(), String::new(), token.line, token.start, token.start + 1);
+        let bracket = Token::new(TokenType::LBrack, "[".to_string(), String::new(), token.line, token.end - 1, token.end);
+
+        self.tokens[self.current] = question;
+        self.tokens.insert(self.current + 1, bracket);
     }
 
-    fn var_declaration(&mut self) -> Result<Stmt, ParserError> {
+    /// Runs `parse` with `colons_expected` set to `count`, putting the old count back afterwards.
+    /// Brackets and blocks start again from 0, as a ':' inside them cannot belong to anything
+    /// outside them
+    fn with_colons_expected<T>(
+        &mut self,
+        count: usize,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParserError>,
+    ) -> Result<T, ParserError> {
+        let previous = std::mem::replace(&mut self.colons_expected, count);
+        let result = parse(self);
+        self.colons_expected = previous;
+        return result;
+    }
+
+    /// Parses a variable declaration after its 'var' or 'const' keyword. Constants must be given
+    /// an initializer, as they cannot be assigned to later
+    fn var_declaration(&mut self, constant: bool) -> Result<Stmt, ParserError> {
+        if self.check(TokenType::LBrack) {
+            return self.destructure_declaration(constant);
+        }
+
         let name = self.consume(TokenType::Identifier, "ExpectedVariableName")?;
+        if constant && !self.check(TokenType::Equal) {
+            return Err(ParserError::ExpectedConstantInitializer {
+                name: name.lexeme,
+                line: name.line,
+            });
+        }
 
         let initializer = if self.match_token(vec![&TokenType::Equal]) {
             let expr = self.expression()?;
@@ -172,7 +390,17 @@ impl Parser {
 
         self.consume(TokenType::Semicolon, "ExpectedSemicolonAfterVariableDeclaration")?;
 
-        return Ok(Stmt::Var { name, initializer });
+        return Ok(Stmt::Var { name, initializer, constant });
+    }
+
+    /// Parses a declaration like `var [a, b, ...rest] = list;`, which must have an initializer
+    fn destructure_declaration(&mut self, constant: bool) -> Result<Stmt, ParserError> {
+        let pattern = self.pattern()?;
+        self.consume(TokenType::Equal, "ExpectedEqualAfterPattern")?;
+        let initializer = self.expression()?;
+        self.consume(TokenType::Semicolon, "ExpectedSemicolonAfterVariableDeclaration")?;
+
+        return Ok(Stmt::Destructure { pattern, initializer, constant });
     }
 
     fn statement(&mut self) -> Result<Stmt, ParserError> {
@@ -182,12 +410,18 @@ impl Parser {
         if self.match_token(vec![&TokenType::If]) {
             return self.if_statement();
         };
-        if self.match_token(vec![&TokenType::Print]) {
+        if self.match_token(vec![&TokenType::Match]) {
+            return self.match_statement();
+        };
+        if self.match_token(vec![&TokenType::Print, &TokenType::Eprint]) {
             return self.print_statement();
         };
         if self.match_token(vec![&TokenType::Return]) {
             return self.return_statement();
         };
+        if self.match_token(vec![&TokenType::Yield]) {
+            return self.yield_statement();
+        };
         if self.match_token(vec![&TokenType::While]) {
             return self.while_statement();
         };
@@ -203,11 +437,15 @@ impl Parser {
     fn for_statement(&mut self) -> Result<Stmt, ParserError> {
         self.consume(TokenType::LParen, "ExpectedLParenAfterFor")?;
 
+        if self.check(TokenType::Identifier) && self.check_next(TokenType::In) {
+            return self.for_in_statement();
+        }
+
         let initializer;
         if self.match_token(vec![&TokenType::Semicolon]) {
             initializer = None;
         } else if self.match_token(vec![&TokenType::Var]) {
-            let var_declaration = self.var_declaration()?;
+            let var_declaration = self.var_declaration(false)?;
             initializer = Some(Box::new(var_declaration));
         } else {
             let expr_stmt = self.expression_statement()?;
@@ -239,6 +477,23 @@ impl Parser {
         });
     }
 
+    /// Parses the rest of a `for (item in iterable)` loop, once the opening `(` has been consumed
+    fn for_in_statement(&mut self) -> Result<Stmt, ParserError> {
+        let name = self.advance().clone();
+        self.advance();
+
+        let iterable = self.expression()?;
+        self.consume(TokenType::RParen, "ExpectedRParenAfterForClauses")?;
+
+        let body = self.statement()?;
+
+        return Ok(Stmt::ForIn {
+            name,
+            iterable,
+            body: Box::new(body),
+        });
+    }
+
     fn if_statement(&mut self) -> Result<Stmt, ParserError> {
         self.consume(TokenType::LParen, "ExpectedLParenAfterIf")?;
         let condition = self.expression()?;
@@ -259,11 +514,121 @@ impl Parser {
         });
     }
 
-    fn print_statement(&mut self) -> Result<Stmt, ParserError> {
+    fn match_statement(&mut self) -> Result<Stmt, ParserError> {
+        let keyword = self.previous().clone();
+        self.consume(TokenType::LParen, "ExpectedLParenAfterMatch")?;
         let value = self.expression()?;
+        self.consume(TokenType::RParen, "ExpectedRParenAfterMatchValue")?;
+        self.consume(TokenType::LBrace, "ExpectedLBraceBeforeMatchArms")?;
+
+        let mut arms = Vec::new();
+        while !self.check(TokenType::RBrace) && !self.is_at_end() {
+            let pattern = self.pattern()?;
+
+            let mut guard = None;
+            if self.match_token(vec![&TokenType::If]) {
+                guard = Some(self.expression()?);
+            }
+
+            self.consume(TokenType::Arrow, "ExpectedArrowAfterPattern")?;
+            let body = self.statement()?;
+
+            // Arms can optionally be separated by commas
+            self.match_token(vec![&TokenType::Comma]);
+
+            arms.push(MatchArm { pattern, guard, body });
+        }
+        self.consume(TokenType::RBrace, "ExpectedRBraceAfterBlock")?;
+
+        return Ok(Stmt::Match { keyword, value, arms });
+    }
+
+    /// Parses a pattern, which may be several alternatives separated by '|'
+    fn pattern(&mut self) -> Result<Pattern, ParserError> {
+        let mut alternatives = vec![self.single_pattern()?];
+        while self.match_token(vec![&TokenType::Pipe]) {
+            alternatives.push(self.single_pattern()?);
+        }
+
+        if alternatives.len() == 1 {
+            return Ok(alternatives.remove(0));
+        }
+        return Ok(Pattern::Or { alternatives });
+    }
+
+    fn single_pattern(&mut self) -> Result<Pattern, ParserError> {
+        if self.match_token(vec![&TokenType::Identifier]) {
+            let name = self.previous().clone();
+            if name.lexeme == "_" {
+                return Ok(Pattern::Wildcard { token: name });
+            }
+            return Ok(Pattern::Binding { name });
+        }
+
+        if self.match_token(vec![&TokenType::LBrack]) {
+            let mut items = Vec::new();
+            let mut rest = None;
+
+            while !self.check(TokenType::RBrack) && !self.is_at_end() {
+                // The rest of the list can only be matched at the end of the pattern
+                if self.match_token(vec![&TokenType::DotDotDot]) {
+                    let mut name = None;
+                    if self.match_token(vec![&TokenType::Identifier]) {
+                        name = Some(self.previous().clone());
+                    }
+                    rest = Some(name);
+                    break;
+                }
+
+                items.push(self.pattern()?);
+                if !self.match_token(vec![&TokenType::Comma]) {
+                    break;
+                }
+            }
+            self.consume(TokenType::RBrack, "ExpectedRBrackAfterPatterns")?;
+
+            return Ok(Pattern::List { items, rest });
+        }
+
+        // Literal patterns are parsed in the same way as literal expressions, with an optional
+        // minus for negative numbers
+        let negative = self.match_token(vec![&TokenType::Minus]);
+        let literal_types = [
+            TokenType::Int,
+            TokenType::Num,
+            TokenType::String,
+            TokenType::True,
+            TokenType::False,
+            TokenType::Null,
+        ];
+        if literal_types.contains(&self.peek().token_type) {
+            if let Expr::Literal { value } = self.primary()? {
+                if !negative {
+                    return Ok(Pattern::Literal { value });
+                }
+                if let Some(n) = Number::from_literal(&value) {
+                    return Ok(Pattern::Literal { value: n.neg().into_literal() });
+                }
+            }
+        }
+
+        let token = self.peek();
+        return Err(ParserError::ExpectedPattern {
+            value: token.lexeme.clone(),
+            line: token.line,
+        });
+    }
+
+    /// Parses `print a, b, c;`, or `eprint` with the same values
+    fn print_statement(&mut self) -> Result<Stmt, ParserError> {
+        let keyword = self.previous().clone();
+        let mut expressions = vec![self.expression()?];
+        while self.match_token(vec![&TokenType::Comma]) {
+            expressions.push(self.expression()?);
+        }
         self.consume(TokenType::Semicolon, "ExpectedSemicolonAfterPrintValue")?;
 
-        return Ok(Stmt::Print { expression: value });
+        return Ok(Stmt::Print { keyword, expressions });
     }
 
     fn return_statement(&mut self) -> Result<Stmt, ParserError> {
@@ -277,6 +642,14 @@ impl Parser {
         return Ok(Stmt::Return { keyword, value });
     }
 
+    fn yield_statement(&mut self) -> Result<Stmt, ParserError> {
+        let keyword = self.previous().clone();
+        let value = self.expression()?;
+        self.consume(TokenType::Semicolon, "ExpectedSemicolonAfterYieldValue")?;
+
+        return Ok(Stmt::Yield { keyword, value });
+    }
+
     fn while_statement(&mut self) -> Result<Stmt, ParserError> {
         self.consume(TokenType::LParen, "ExpectedLParenAfterWhile")?;
         let condition = self.expression()?;
@@ -288,12 +661,14 @@ impl Parser {
     }
 
     fn block(&mut self) -> Result<Vec<Stmt>, ParserError> {
-        let mut statements = Vec::new();
-
-        while !self.check(TokenType::RBrace) && !self.is_at_end() {
-            let stmt = self.declaration()?;
-            statements.push(stmt);
-        }
+        // A lambda's block can be inside a conditional, but the statements in it are not
+        let statements = self.with_colons_expected(0, |parser| {
+            let mut statements = Vec::new();
+            while !parser.check(TokenType::RBrace) && !parser.is_at_end() {
+                statements.push(parser.declaration()?);
+            }
+            return Ok(statements);
+        })?;
         self.consume(TokenType::RBrace, "ExpectedRBraceAfterBlock")?;
 
         return Ok(statements);
@@ -304,39 +679,9 @@ impl Parser {
     }
 
     fn assignment(&mut self) -> Result<Expr, ParserError> {
-        let expr = self.or()?;
+        let expr = self.conditional()?;
 
-        if self.match_token(vec![&TokenType::Incr, &TokenType::Decr]) {
-            match expr {
-                Expr::Var { name } => match self.previous().token_type {
-                    TokenType::Incr => {
-                        return Ok(Expr::Alteration {
-                            name,
-                            alteration_type: TokenType::Incr,
-                        })
-                    }
-                    TokenType::Decr => {
-                        return Ok(Expr::Alteration {
-                            name,
-                            alteration_type: TokenType::Decr,
-                        })
-                    }
-                    _ => {
-                        let token = self.previous();
-                        return Err(ParserError::ExpectedAlterationExpression {
-                            line: token.start,
-                        });
-                    }
-                },
-                _ => {
-                    let token = self.previous();
-                    return Err(ParserError::InvalidAlterationTarget {
-                        target: token.lexeme.clone(),
-                        line: token.line,
-                    });
-                }
-            }
-        } else if self.match_token(vec![&TokenType::Equal]) {
+        if self.match_token(vec![&TokenType::Equal]) {
             let value = self.assignment()?;
 
             match expr {
@@ -346,6 +691,27 @@ impl Parser {
                         value: Box::new(value),
                     })
                 }
+                Expr::Splice { list, is_splice: false, start: Some(index), end: None, optional: false } => {
+                    return Ok(Expr::IndexAssign {
+                        list,
+                        index,
+                        value: Box::new(value),
+                    })
+                }
+                Expr::List { .. } => {
+                    if let Some(pattern) = self.assignment_pattern(&expr) {
+                        return Ok(Expr::DestructureAssign {
+                            pattern,
+                            value: Box::new(value),
+                        });
+                    }
+
+                    let token = self.previous();
+                    return Err(ParserError::InvalidAssignmentTarget {
+                        target: token.lexeme.clone(),
+                        line: token.line,
+                    });
+                }
                 _ => {
                     let token = self.previous();
                     return Err(ParserError::InvalidAssignmentTarget {
@@ -354,6 +720,113 @@ impl Parser {
                     });
                 }
             }
+        } else if self.match_token(vec![
+            &TokenType::PlusEqual,
+            &TokenType::MinusEqual,
+            &TokenType::AsteriskEqual,
+            &TokenType::FSlashEqual,
+            &TokenType::PercentEqual,
+        ]) {
+            let operator = self.previous().clone();
+            let value = self.assignment()?;
+
+            if !self.is_assignable(&expr) {
+                return Err(ParserError::InvalidAssignmentTarget {
+                    target: operator.lexeme,
+                    line: operator.line,
+                });
+            }
+
+            return Ok(Expr::CompoundAssign {
+                target: Box::new(expr),
+                operator,
+                value: Box::new(value),
+            });
+        }
+
+        return Ok(expr);
+    }
+
+    /// Converts a list of variables on the left of an '=' into the pattern they are assigned by,
+    /// or gives `None` if it contains anything other than variables and nested lists
+    fn assignment_pattern(&self, expr: &Expr) -> Option<Pattern> {
+        return match expr {
+            Expr::Var { name } if name.lexeme == "_" => Some(Pattern::Wildcard { token: name.clone() }),
+            Expr::Var { name } => Some(Pattern::Binding { name: name.clone() }),
+            Expr::List { items } => {
+                let items = items
+                    .iter()
+                    .map(|item| self.assignment_pattern(item))
+                    .collect::<Option<Vec<Pattern>>>()?;
+                Some(Pattern::List { items, rest: None })
+            }
+            _ => None,
+        };
+    }
+
+    /// Whether an expression can be assigned to by a compound assignment or alteration, which is
+    /// true for variables and single list elements
+    fn is_assignable(&self, expr: &Expr) -> bool {
+        return matches!(
+            expr,
+            Expr::Var { .. }
+                | Expr::Splice { is_splice: false, start: Some(_), end: None, optional: false, .. }
+        );
+    }
+
+    /// Creates an increment or decrement expression once its operator and target are parsed
+    fn alteration(&self, operator: Token, target: Expr, prefix: bool) -> Result<Expr, ParserError> {
+        if !self.is_assignable(&target) {
+            return Err(ParserError::InvalidAlterationTarget {
+                target: operator.lexeme,
+                line: operator.line,
+            });
+        }
+
+        return Ok(Expr::Alteration {
+            target: Box::new(target),
+            alteration_type: operator.token_type,
+            prefix,
+        });
+    }
+
+    /// Parses `condition ? a : b`, which is right associative so that conditionals can be chained
+    fn conditional(&mut self) -> Result<Expr, ParserError> {
+        let condition = self.coalesce()?;
+
+        // `?[` is lexed as one token, so when it was not taken as an optional index it is split
+        // back into the '?' of a conditional and the '[' of a list
+        if self.check(TokenType::QuestionLBrack) {
+            self.split_question_lbrack();
+        }
+
+        if self.match_token(vec![&TokenType::Question]) {
+            let then_branch = self.with_colons_expected(self.colons_expected + 1, Self::expression)?;
+            self.consume(TokenType::Colon, "ExpectedColonInConditional")?;
+            let else_branch = self.conditional()?;
+
+            return Ok(Expr::Conditional {
+                condition: Box::new(condition),
+                then_branch: Box::new(then_branch),
+                else_branch: Box::new(else_branch),
+            });
+        }
+
+        return Ok(condition);
+    }
+
+    /// Parses `a ?? b`, which is a logical expression that only evaluates `b` if `a` is null
+    fn coalesce(&mut self) -> Result<Expr, ParserError> {
+        let mut expr = self.or()?;
+
+        while self.match_token(vec![&TokenType::QuestionQuestion]) {
+            let operator = self.previous().clone();
+            let right = self.or()?;
+            expr = Expr::Logical {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
         }
 
         return Ok(expr);
@@ -408,7 +881,7 @@ impl Parser {
     }
 
     fn comparison(&mut self) -> Result<Expr, ParserError> {
-        let mut expr: Expr = self.term()?;
+        let mut expr: Expr = self.range()?;
 
         while self.match_token(vec![
             &TokenType::Greater,
@@ -419,7 +892,7 @@ impl Parser {
             &TokenType::EqualEqual,
         ]) {
             let operator = self.previous().clone();
-            let right = self.term()?;
+            let right = self.range()?;
             expr = Expr::Binary {
                 left: Box::new(expr.clone()),
                 operator,
@@ -430,6 +903,93 @@ impl Parser {
         return Ok(expr);
     }
 
+    fn range(&mut self) -> Result<Expr, ParserError> {
+        let expr = self.bit_or()?;
+
+        if self.match_token(vec![&TokenType::DotDot, &TokenType::DotDotEqual]) {
+            let operator = self.previous().clone();
+            let end = self.bit_or()?;
+
+            let mut step = None;
+            if self.match_token(vec![&TokenType::DotDot]) {
+                step = Some(Box::new(self.bit_or()?));
+            }
+
+            return Ok(Expr::Range {
+                start: Box::new(expr),
+                operator,
+                end: Box::new(end),
+                step,
+            });
+        }
+
+        return Ok(expr);
+    }
+
+    fn bit_or(&mut self) -> Result<Expr, ParserError> {
+        let mut expr = self.bit_xor()?;
+
+        while self.match_token(vec![&TokenType::Pipe]) {
+            let operator = self.previous().clone();
+            let right = self.bit_xor()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+
+        return Ok(expr);
+    }
+
+    fn bit_xor(&mut self) -> Result<Expr, ParserError> {
+        let mut expr = self.bit_and()?;
+
+        while self.match_token(vec![&TokenType::Caret]) {
+            let operator = self.previous().clone();
+            let right = self.bit_and()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+
+        return Ok(expr);
+    }
+
+    fn bit_and(&mut self) -> Result<Expr, ParserError> {
+        let mut expr = self.shift()?;
+
+        while self.match_token(vec![&TokenType::Ampersand]) {
+            let operator = self.previous().clone();
+            let right = self.shift()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+
+        return Ok(expr);
+    }
+
+    fn shift(&mut self) -> Result<Expr, ParserError> {
+        let mut expr = self.term()?;
+
+        while self.match_token(vec![&TokenType::LessLess, &TokenType::GreaterGreater]) {
+            let operator = self.previous().clone();
+            let right = self.term()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+
+        return Ok(expr);
+    }
+
     fn term(&mut self) -> Result<Expr, ParserError> {
         let mut expr = self.factor()?;
 
@@ -449,7 +1009,12 @@ impl Parser {
     fn factor(&mut self) -> Result<Expr, ParserError> {
         let mut expr = self.unary()?;
 
-        while self.match_token(vec![&TokenType::FSlash, &TokenType::Asterisk]) {
+        while self.match_token(vec![
+            &TokenType::FSlash,
+            &TokenType::Asterisk,
+            &TokenType::Percent,
+            &TokenType::FSlashFSlash,
+        ]) {
             let operator = self.previous().clone();
             let right = self.unary()?;
             expr = Expr::Binary {
@@ -463,7 +1028,7 @@ impl Parser {
     }
 
     fn unary(&mut self) -> Result<Expr, ParserError> {
-        if self.match_token(vec![&TokenType::Bang, &TokenType::Minus]) {
+        if self.match_token(vec![&TokenType::Bang, &TokenType::Minus, &TokenType::Tilde]) {
             let operator = self.previous().clone();
             let right = self.unary()?;
             return Ok(Expr::Unary {
@@ -472,7 +1037,42 @@ impl Parser {
             });
         }
 
-        return self.call();
+        if self.match_token(vec![&TokenType::Incr, &TokenType::Decr]) {
+            let operator = self.previous().clone();
+            let target = self.unary()?;
+            return self.alteration(operator, target, true);
+        }
+
+        return self.exponent();
+    }
+
+    /// Exponents bind more tightly than unary operators, so `-2 ** 2` is `-4`, and are right
+    /// associative, so `2 ** 3 ** 2` is `2 ** 9`
+    fn exponent(&mut self) -> Result<Expr, ParserError> {
+        let expr = self.postfix()?;
+
+        if self.match_token(vec![&TokenType::AsteriskAsterisk]) {
+            let operator = self.previous().clone();
+            let right = self.unary()?;
+            return Ok(Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            });
+        }
+
+        return Ok(expr);
+    }
+
+    fn postfix(&mut self) -> Result<Expr, ParserError> {
+        let expr = self.call()?;
+
+        if self.match_token(vec![&TokenType::Incr, &TokenType::Decr]) {
+            let operator = self.previous().clone();
+            return self.alteration(operator, expr, false);
+        }
+
+        return Ok(expr);
     }
 
     fn call(&mut self) -> Result<Expr, ParserError> {
@@ -481,20 +1081,20 @@ impl Parser {
         loop {
             if self.match_token(vec![&TokenType::LParen]) {
                 expr = self.finish_call(expr)?;
-            } else if self.match_token(vec![&TokenType::Dot]) {
-                let call = self.call()?;
-                let name = match expr {
-                    Expr::Var { ref name } => name,
-                    _ => {
-                        let token = self.peek();
-                        return Err(ParserError::CanOnlyCallIdentifiers {
-                            value: token.lexeme.clone(),
-                            line: token.line,
-                        })
-                    },
-                };
+            } else if self.match_token(vec![&TokenType::Dot, &TokenType::QuestionDot]) {
+                let optional = self.previous().token_type == TokenType::QuestionDot;
+                let name = self.consume(TokenType::Identifier, "ExpectedMethodName")?.clone();
+                let mut call = Expr::Var { name };
+                if self.match_token(vec![&TokenType::LParen]) {
+                    call = self.finish_call(call)?;
+                }
 
-                return Ok(Expr::ListMethodCall { object: name.clone(), call: Box::new(call) })
+                // Methods on a variable can change the list it holds, so they are kept apart from
+                // methods on any other value
+                expr = match expr {
+                    Expr::Var { name } => Expr::ListMethodCall { object: name, call: Box::new(call), optional },
+                    object => Expr::MethodCall { object: Box::new(object), call: Box::new(call), optional },
+                };
             } else {
                 break;
             }
@@ -505,14 +1105,30 @@ impl Parser {
 
     fn finish_call(&mut self, callee: Expr) -> Result<Expr, ParserError> {
         let mut arguments: Vec<Expr> = Vec::new();
+        let mut keywords: Vec<(Token, Expr)> = Vec::new();
 
         if !self.check(TokenType::RParen) {
             loop {
-                if arguments.len() >= 255 {
+                if arguments.len() + keywords.len() >= 255 {
                     return Err(ParserError::TooManyArguments { callee });
                 }
-                let expr = self.expression()?;
-                arguments.push(expr);
+
+                // Keyword arguments are written as `name: value`, and must come after every
+                // positional argument
+                let is_keyword = self.check(TokenType::Identifier)
+                    && self.tokens.get(self.current + 1).is_some_and(|t| t.token_type == TokenType::Colon);
+                if is_keyword {
+                    let name = self.advance().clone();
+                    self.advance();
+                    keywords.push((name, self.with_colons_expected(0, Self::expression)?));
+                } else if !keywords.is_empty() {
+                    return Err(ParserError::PositionalArgumentAfterKeyword {
+                        line: self.peek().line,
+                    });
+                } else {
+                    arguments.push(self.with_colons_expected(0, Self::expression)?);
+                }
+
                 if !self.match_token(vec![&TokenType::Comma]) {
                     break;
                 };
@@ -524,6 +1140,7 @@ impl Parser {
         return Ok(Expr::Call {
             callee: Box::new(callee),
             arguments,
+            keywords,
         });
     }
 
@@ -544,13 +1161,35 @@ impl Parser {
             });
         };
 
-        if self.match_token(vec![&TokenType::Num, &TokenType::String]) {
+        // A float written without the 0 before its point, like `.5`, is lexed as a '.' straight
+        // before an int
+        let leading_point = self.check(TokenType::Dot)
+            && self.tokens.get(self.current + 1).is_some_and(|t| {
+                t.token_type == TokenType::Int
+                    && t.start == self.peek().end
+                    && t.lexeme.chars().all(|c| c.is_ascii_digit() || c == '_')
+            });
+        if leading_point {
+            self.advance();
+            let token = self.advance().clone();
+            return match format!("0.{}", token.literal).parse() {
+                Ok(n) => Ok(Expr::Literal { value: LiteralType::Num(n) }),
+                Err(_) => Err(ParserError::UnableToParseLiteralToFloat {
+                    value: format!(".{}", token.lexeme),
+                    line: token.line,
+                }),
+            };
+        }
+
+        if self.match_token(vec![&TokenType::Int, &TokenType::Num, &TokenType::String]) {
             match self.previous().token_type {
                 TokenType::String => {
                     return Ok(Expr::Literal {
                         value: LiteralType::Str(self.previous().literal.clone()),
                     })
                 }
+                // The lexer only produces plain decimal literals, so neither of these parses
+                // should fail
                 TokenType::Num => {
                     let n = match self.previous().literal.clone().trim().parse() {
                         Ok(v) => v,
@@ -566,6 +1205,24 @@ impl Parser {
                         value: LiteralType::Num(n),
                     });
                 }
+                TokenType::Int => {
+                    let literal = self.previous().literal.trim();
+                    // Literals too large for an i64 become a BigInt straight away
+                    let value = match literal.parse::<i64>() {
+                        Ok(n) => LiteralType::Int(n),
+                        Err(_) => match BigInt::parse(literal) {
+                            Some(n) => LiteralType::BigInt(n),
+                            None => {
+                                let token = self.previous();
+                                return Err(ParserError::UnableToParseLiteralToInt {
+                                    value: token.lexeme.clone(),
+                                    line: token.line,
+                                });
+                            }
+                        },
+                    };
+                    return Ok(Expr::Literal { value });
+                }
                 _ => {
                     let token = self.previous();
                     return Err(ParserError::ExpectedStringOrNumber {
@@ -578,39 +1235,42 @@ impl Parser {
 
         if self.match_token(vec![&TokenType::Identifier]) {
             let name = self.previous().clone();
-            let expr = if self.match_token(vec![&TokenType::LBrack]) {
+            let indexed = self.check(TokenType::LBrack)
+                || (self.check(TokenType::QuestionLBrack) && !self.is_conditional_list());
+
+            let expr = if indexed {
+                let optional = self.advance().token_type == TokenType::QuestionLBrack;
                 let mut start: Option<Box<Expr>> = None;
                 let mut end: Option<Box<Expr>> = None;
                 let mut is_splice = false;
                 if self.peek().token_type != TokenType::Colon {
-                    start = Some(Box::new(self.expression()?));
+                    let index = self.with_colons_expected(1, Self::expression)?;
+                    start = Some(Box::new(index));
                 }
-                start = if start.is_some() {
-                    Some(start.unwrap())
-                } else {
-                    None
-                };
                 if self.match_token(vec![&TokenType::Colon]) {
                     is_splice = true;
                     if self.peek().token_type != TokenType::RBrack {
-                        end = Some(Box::new(self.expression()?));
+                        end = Some(Box::new(self.with_colons_expected(0, Self::expression)?));
                     }
-                    end = if end.is_some() {
-                        Some(end.unwrap())
-                    } else {
-                        None
-                    };
                 }
                 self.consume(TokenType::RBrack, "ExpectedRBrackAfterIndex")?;
-                Expr::Splice { list: name, is_splice, start, end }
+                Expr::Splice { list: name, is_splice, start, end, optional }
             } else {
                 Expr::Var { name: name.clone() }
             };
             return Ok(expr);
         }
 
+        if self.match_token(vec![&TokenType::Def]) {
+            return self.lambda();
+        }
+
+        if self.check(TokenType::LParen) && self.is_arrow_function() {
+            return self.arrow_function();
+        }
+
         if self.match_token(vec![&TokenType::LParen]) {
-            let expr = self.expression()?;
+            let expr = self.with_colons_expected(0, Self::expression)?;
             self.consume(TokenType::RParen, "ExpectedRParenAfterExpression")?;
             return Ok(Expr::Grouping {
                 expression: Box::new(expr),
@@ -624,9 +1284,9 @@ impl Parser {
             }
             loop {
                 if self.match_token(vec![&TokenType::RBrack]) {
-                    break;
+                    return Ok(Expr::List { items });
                 }
-                items.push(self.expression()?);
+                items.push(self.with_colons_expected(0, Self::expression)?);
                 if !self.match_token(vec![&TokenType::Comma]) {
                     break;
                 }
@@ -673,6 +1333,13 @@ impl Parser {
         return self.peek().token_type == token_type;
     }
 
+    fn check_next(&self, token_type: TokenType) -> bool {
+        return match self.tokens.get(self.current + 1) {
+            Some(token) => token.token_type == token_type,
+            None => false,
+        };
+    }
+
     fn advance(&mut self) -> &Token {
         if !self.is_at_end() {
             self.current += 1
@@ -703,12 +1370,18 @@ impl Parser {
 
             match self.peek().token_type {
                 TokenType::Class
+                | TokenType::Const
                 | TokenType::Def
+                | TokenType::Export
+                | TokenType::From
+                | TokenType::Import
                 | TokenType::Var
                 | TokenType::For
                 | TokenType::If
+                | TokenType::Match
                 | TokenType::While
                 | TokenType::Print
+                | TokenType::Eprint
                 | TokenType::Return => return,
                 _ => {
                     self.advance();
@@ -781,6 +1454,13 @@ impl Parser {
                     line: token.line,
                 })
             },
+            "ExpectedSemicolonAfterYieldValue" => {
+                let token = self.previous();
+                Err(ParserError::ExpectedSemicolonAfterYieldValue {
+                    value: token.lexeme.clone(),
+                    line: token.line,
+                })
+            },
             "ExpectedLParenAfterWhile" => {
                 let token = self.peek();
                 Err(ParserError::ExpectedLParenAfterWhile {
@@ -793,6 +1473,13 @@ impl Parser {
                     line: token.line,
                 })
             },
+            "ExpectedMethodName" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedMethodName {
+                    value: token.lexeme.clone(),
+                    line: token.line,
+                })
+            },
             "ExpectedRParenAfterArguments" => {
                 let token = self.peek();
                 Err(ParserError::ExpectedRParenAfterArguments {
@@ -831,6 +1518,96 @@ impl Parser {
                     line: token.line,
                 })
             },
+            "ExpectedRParenAfterParameters" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedRParenAfterParameters {
+                    line: token.line,
+                })
+            },
+            "ExpectedLBraceBeforeFunctionBody" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedLBraceBeforeFunctionBody {
+                    line: token.line,
+                })
+            },
+            "ExpectedLParenAfterDef" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedLParenAfterDef {
+                    line: token.line,
+                })
+            },
+            "ExpectedArrow" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedArrow {
+                    line: token.line,
+                })
+            },
+            "ExpectedColonInConditional" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedColonInConditional {
+                    line: token.line,
+                })
+            },
+            "ExpectedLParenAfterMatch" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedLParenAfterMatch {
+                    line: token.line,
+                })
+            },
+            "ExpectedRParenAfterMatchValue" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedRParenAfterMatchValue {
+                    line: token.line,
+                })
+            },
+            "ExpectedLBraceBeforeMatchArms" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedLBraceBeforeMatchArms {
+                    line: token.line,
+                })
+            },
+            "ExpectedArrowAfterPattern" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedArrowAfterPattern {
+                    line: token.line,
+                })
+            },
+            "ExpectedRBrackAfterPatterns" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedRBrackAfterPatterns {
+                    line: token.line,
+                })
+            },
+            "ExpectedEqualAfterPattern" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedEqualAfterPattern {
+                    line: token.line,
+                })
+            },
+            "ExpectedAsAfterImport" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedAsAfterImport {
+                    line: token.line,
+                })
+            },
+            "ExpectedImportAfterFrom" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedImportAfterFrom {
+                    line: token.line,
+                })
+            },
+            "ExpectedImportName" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedImportName {
+                    line: token.line,
+                })
+            },
+            "ExpectedSemicolonAfterImport" => {
+                let token = self.peek();
+                Err(ParserError::ExpectedSemicolonAfterImport {
+                    line: token.line,
+                })
+            },
             "ExpectedRBrackAfterValues" => {
                 let token = self.peek();
                 Err(ParserError::ExpectedRBrackAfterValues {
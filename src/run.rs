@@ -6,11 +6,18 @@ use crate::{
 };
 
 pub fn run(source: &str) {
+    let mut interpreter = Interpreter::new();
+    run_in(&mut interpreter, source);
+}
+
+/// Runs the source code in an existing interpreter, so that anything the host has registered on
+/// it, such as native functions, is available to the script
+pub fn run_in(interpreter: &mut Interpreter, source: &str) {
     let mut lexer = Lexer::new(source.to_string());
     let tokens = match lexer.run() {
         Ok(tokens) => tokens,
         Err(e) => {
-            eprintln!("A lexer error occured: {e}");
+            interpreter.output().write_err(&format!("A lexer error occured: {e}"));
             return;
         }
     };
@@ -19,25 +26,29 @@ pub fn run(source: &str) {
     let ast = match parser.parse() {
         Ok(ast) => ast,
         Err(e) => {
-            eprintln!("A parser error occured: {e}");
+            interpreter.output().write_err(&format!("A parser error occured: {e}"));
             return;
         }
     };
 
     let mut semantic_analyser = SemanticAnalyser::new(ast.clone());
+    semantic_analyser.declare_builtins(interpreter.builtins.borrow().names());
+    semantic_analyser.declare_globals(interpreter.globals.borrow().names());
     match semantic_analyser.run() {
         Ok(_) => {}
         Err(e) => {
-            eprintln!("A semantic error occured: {e}");
+            interpreter.output().write_err(&format!("A semantic error occured: {e}"));
             return;
         }
     }
+    for warning in semantic_analyser.warnings() {
+        interpreter.output().write_err(&format!("Warning: {warning}"));
+    }
 
-    let mut interpreter = Interpreter::new();
     match interpreter.interpret(ast) {
         Ok(_) => {},
         Err(e) => {
-            eprintln!("An interpreter error occured: {e}");
+            interpreter.output().write_err(&format!("An interpreter error occured: {e}"));
             return;
         }
     }
@@ -1,14 +1,17 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
-    error::SemanticAnalyserError,
+    error::{SemanticAnalyserError, SemanticAnalyserWarning},
     expr::{self, Expr},
-    stmt::{self, Stmt},
+    list::List,
+    pattern::Pattern,
+    stmt::{self, Parameter, Stmt},
+    token::Token,
 };
 
 #[derive(Debug)]
 enum Symbol {
-    Ident { initialised: bool },
+    Ident { initialised: bool, mutable: bool }, // Variables declared with 'const' are not mutable
 }
 
 #[derive(Clone, PartialEq)]
@@ -20,8 +23,10 @@ enum FunctionType {
 pub struct SemanticAnalyser {
     ast: Vec<Stmt>,
     symbol_tables: Vec<HashMap<String, Symbol>>,
+    builtins: HashSet<String>, // Defined outside the script, so they can be shadowed but not assigned to
     curr: usize,
     func_type: FunctionType,
+    warnings: Vec<SemanticAnalyserWarning>,
 }
 
 impl SemanticAnalyser {
@@ -29,11 +34,27 @@ impl SemanticAnalyser {
         Self {
             ast,
             symbol_tables: vec![HashMap::<String, Symbol>::new()],
+            builtins: HashSet::new(),
             curr: 0,
             func_type: FunctionType::None,
+            warnings: Vec::new(),
         }
     }
 
+    /// Declares names that are already defined in the interpreter's global environment, such as
+    /// native functions registered by the host
+    pub fn declare_globals(&mut self, names: Vec<String>) {
+        for name in names {
+            self.symbol_tables[0].insert(name, Symbol::Ident { initialised: true, mutable: true });
+        }
+    }
+
+    /// Declares the interpreter's builtins, such as native functions, which live in a scope outside
+    /// the top level so that the script can declare its own variables with the same names
+    pub fn declare_builtins(&mut self, names: Vec<String>) {
+        self.builtins.extend(names);
+    }
+
     pub fn run(&mut self) -> Result<(), SemanticAnalyserError> {
         for stmt in self.ast.clone() {
             stmt.accept_stmt(self)?;
@@ -42,6 +63,10 @@ impl SemanticAnalyser {
         return Ok(());
     }
 
+    /// Problems found during the last run that do not stop the program from running
+    pub fn warnings(&self) -> &[SemanticAnalyserWarning] {
+        return &self.warnings;
+    }
 
     fn begin_scope(&mut self) {
         let st: HashMap<String, Symbol> = HashMap::new();
@@ -55,6 +80,10 @@ impl SemanticAnalyser {
     }
 
     fn check_declared(&mut self, name: &String) -> bool {
+        if self.builtins.contains(name) {
+            return true;
+        }
+
         if self.curr == 0 {
             if self.symbol_tables[0].contains_key(name) {
                 return true;
@@ -73,7 +102,7 @@ impl SemanticAnalyser {
     fn check_defined(&mut self, ident_name: &String) -> bool {
         if let Some(sym) = self.symbol_tables[self.curr].get(ident_name) {
             match sym {
-                Symbol::Ident { initialised } => {
+                Symbol::Ident { initialised, .. } => {
                     if *initialised {
                         return true;
                     }
@@ -85,45 +114,149 @@ impl SemanticAnalyser {
         return false;
     }
 
-    fn pass_function(&mut self, stmt: &Stmt, declaration: FunctionType) -> Result<(), SemanticAnalyserError> {
-        match stmt {
-            Stmt::Function { name, params, body } => {
-                let sym = Symbol::Ident { initialised: true };
-                
-                if self.symbol_tables[self.curr].contains_key(&name.lexeme) {
-                    return Err(SemanticAnalyserError::VariableAlreadyAssignedInScope {
-                        name: name.lexeme.clone(),
-                    });
-                }
-                self.symbol_tables[self.curr].insert(name.lexeme.clone(), sym);
+    fn resolve_function(
+        &mut self,
+        params: &[Parameter],
+        body: &[Stmt],
+        declaration: FunctionType,
+    ) -> Result<(), SemanticAnalyserError> {
+        self.begin_scope();
+
+        let enclosing = self.func_type.clone();
+        self.func_type = declaration;
+
+        for param in params {
+            let sym = Symbol::Ident {
+                initialised: true,
+                mutable: true,
+            };
+
+            // Default values are evaluated in the function's scope, so they can use the
+            // parameters before them
+            if let Some(default) = &param.default {
+                default.accept_expr(self)?;
+            }
 
-                self.begin_scope();
+            if self.symbol_tables[self.curr].contains_key(&param.name.lexeme) {
+                return Err(SemanticAnalyserError::VariableAlreadyAssignedInScope {
+                    name: param.name.lexeme.clone(),
+                });
+            }
+            self.symbol_tables[self.curr].insert(param.name.lexeme.clone(), sym);
+        }
 
-                let is_closure = self.func_type.clone() == FunctionType::Function;
-                self.func_type = declaration;
+        for statement in body {
+            statement.accept_stmt(self)?;
+        }
 
-                for param in params {
-                    let sym = Symbol::Ident {
-                        initialised: true,
-                    };
+        self.end_scope();
+        self.func_type = enclosing;
+
+        return Ok(());
+    }
 
-                    if self.symbol_tables[self.curr].contains_key(&param.lexeme) {
-                        return Err(SemanticAnalyserError::VariableAlreadyAssignedInScope {
-                            name: param.lexeme.clone(),
+    /// Checks that every alternative of an or-pattern binds the same variables, as the arm body
+    /// could otherwise use a variable that was never bound
+    fn check_pattern(&self, pattern: &Pattern) -> Result<(), SemanticAnalyserError> {
+        match pattern {
+            Pattern::Or { alternatives } => {
+                let names = |p: &Pattern| {
+                    let mut names: Vec<String> = p.bindings().into_iter().map(|t| t.lexeme).collect();
+                    names.sort();
+                    names
+                };
+
+                let expected = names(&alternatives[0]);
+                for alternative in alternatives {
+                    self.check_pattern(alternative)?;
+                    if names(alternative) != expected {
+                        return Err(SemanticAnalyserError::InconsistentPatternBindings {
+                            pattern: pattern.to_string(),
                         });
                     }
-                    self.symbol_tables[self.curr].insert(param.lexeme.clone(), sym);
                 }
+            }
+            Pattern::List { items, .. } => {
+                for item in items {
+                    self.check_pattern(item)?;
+                }
+            }
+            _ => {}
+        }
 
-                for statement in body {
-                    statement.accept_stmt(self)?;
+        return Ok(());
+    }
+
+    /// Declares every variable bound by a pattern in the current scope
+    fn declare_pattern(&mut self, pattern: &Pattern, mutable: bool) -> Result<(), SemanticAnalyserError> {
+        self.check_pattern(pattern)?;
+
+        for name in pattern.bindings() {
+            if self.symbol_tables[self.curr].contains_key(&name.lexeme) {
+                return Err(SemanticAnalyserError::VariableAlreadyAssignedInScope {
+                    name: name.lexeme.clone(),
+                });
+            }
+            let sym = Symbol::Ident {
+                initialised: true,
+                mutable,
+            };
+            self.symbol_tables[self.curr].insert(name.lexeme.clone(), sym);
+        }
+
+        return Ok(());
+    }
+
+    /// Checks that a variable being assigned to was not declared with 'const'. Variables that
+    /// cannot be found are left for the other checks to report
+    fn check_mutable(&self, name: &Token) -> Result<(), SemanticAnalyserError> {
+        for table in self.symbol_tables[..=self.curr].iter().rev() {
+            if let Some(Symbol::Ident { mutable, .. }) = table.get(&name.lexeme) {
+                if !mutable {
+                    return Err(SemanticAnalyserError::AssignmentToConstant {
+                        name: name.lexeme.clone(),
+                    });
                 }
+                return Ok(());
+            }
+        }
 
-                self.end_scope();
+        if self.builtins.contains(&name.lexeme) {
+            return Err(SemanticAnalyserError::AssignmentToConstant {
+                name: name.lexeme.clone(),
+            });
+        }
+        return Ok(());
+    }
 
-                if !is_closure {
-                    self.func_type = FunctionType::None;
+    /// Checks the target of a compound assignment or alteration, which must be a declared variable
+    /// or a single element of a list
+    fn resolve_target(&mut self, target: &Expr) -> Result<(), SemanticAnalyserError> {
+        match target {
+            Expr::Var { name }
+            | Expr::Splice { list: name, is_splice: false, start: Some(_), end: None, optional: false, .. } => {
+                target.accept_expr(self)?;
+                return self.check_mutable(name);
+            }
+            _ => return Err(SemanticAnalyserError::InvalidAssignmentTarget {
+                target: target.clone(),
+            }),
+        }
+    }
+
+    fn pass_function(&mut self, stmt: &Stmt, declaration: FunctionType) -> Result<(), SemanticAnalyserError> {
+        match stmt {
+            Stmt::Function { name, params, body } => {
+                let sym = Symbol::Ident { initialised: true, mutable: true };
+                
+                if self.symbol_tables[self.curr].contains_key(&name.lexeme) {
+                    return Err(SemanticAnalyserError::VariableAlreadyAssignedInScope {
+                        name: name.lexeme.clone(),
+                    });
                 }
+                self.symbol_tables[self.curr].insert(name.lexeme.clone(), sym);
+
+                self.resolve_function(params, body, declaration)?;
 
                 return Ok(());
             },
@@ -140,14 +273,7 @@ impl SemanticAnalyser {
 impl expr::ExprVisitor<Result<(), SemanticAnalyserError>> for SemanticAnalyser {
     fn visit_alteration_expr(&mut self, expr: &Expr) -> Result<(), SemanticAnalyserError> {
         match expr {
-            Expr::Alteration { name, .. } => {
-                if self.check_declared(&name.lexeme) {
-                    return Ok(());
-                }
-                return Err(SemanticAnalyserError::VariableNotFound {
-                    name: name.lexeme.clone(),
-                });
-            }
+            Expr::Alteration { target, .. } => return self.resolve_target(target),
             _ => return Err(SemanticAnalyserError::DifferentExpression {
                 expr: expr.clone(),
                 expected: "alteration".to_string(),
@@ -161,7 +287,7 @@ impl expr::ExprVisitor<Result<(), SemanticAnalyserError>> for SemanticAnalyser {
                 value.accept_expr(self)?;
 
                 if self.check_declared(&name.lexeme) {
-                    return Ok(());
+                    return self.check_mutable(name);
                 }
 
                 return Err(SemanticAnalyserError::VariableNotFound {
@@ -175,6 +301,29 @@ impl expr::ExprVisitor<Result<(), SemanticAnalyserError>> for SemanticAnalyser {
         }
     }
 
+    fn visit_destructureassign_expr(&mut self, expr: &Expr) -> Result<(), SemanticAnalyserError> {
+        match expr {
+            Expr::DestructureAssign { pattern, value } => {
+                value.accept_expr(self)?;
+
+                for name in pattern.bindings() {
+                    if !self.check_declared(&name.lexeme) {
+                        return Err(SemanticAnalyserError::VariableNotFound {
+                            name: name.lexeme.clone(),
+                        });
+                    }
+                    self.check_mutable(&name)?;
+                }
+
+                return Ok(());
+            }
+            _ => return Err(SemanticAnalyserError::DifferentExpression {
+                expr: expr.clone(),
+                expected: "destructure assign".to_string(),
+            }),
+        }
+    }
+
     fn visit_binary_expr(&mut self, expr: &Expr) -> Result<(), SemanticAnalyserError> {
         match expr {
             Expr::Binary { left, operator: _, right } => {
@@ -191,12 +340,15 @@ impl expr::ExprVisitor<Result<(), SemanticAnalyserError>> for SemanticAnalyser {
 
     fn visit_call_expr(&mut self, expr: &Expr) -> Result<(), SemanticAnalyserError> {
         match expr {
-            Expr::Call { callee, arguments } => {
+            Expr::Call { callee, arguments, keywords } => {
                 callee.accept_expr(self)?;
 
                 for argument in arguments {
                     argument.accept_expr(self)?;
                 }
+                for (_, argument) in keywords {
+                    argument.accept_expr(self)?;
+                }
 
                 return Ok(());
             }
@@ -207,6 +359,35 @@ impl expr::ExprVisitor<Result<(), SemanticAnalyserError>> for SemanticAnalyser {
         }
     }
 
+    fn visit_compoundassign_expr(&mut self, expr: &Expr) -> Result<(), SemanticAnalyserError> {
+        match expr {
+            Expr::CompoundAssign { target, operator: _, value } => {
+                value.accept_expr(self)?;
+                return self.resolve_target(target);
+            }
+            _ => return Err(SemanticAnalyserError::DifferentExpression {
+                expr: expr.clone(),
+                expected: "compound assign".to_string(),
+            }),
+        }
+    }
+
+    fn visit_conditional_expr(&mut self, expr: &Expr) -> Result<(), SemanticAnalyserError> {
+        match expr {
+            Expr::Conditional { condition, then_branch, else_branch } => {
+                condition.accept_expr(self)?;
+                then_branch.accept_expr(self)?;
+                else_branch.accept_expr(self)?;
+
+                return Ok(());
+            }
+            _ => return Err(SemanticAnalyserError::DifferentExpression {
+                expr: expr.clone(),
+                expected: "conditional".to_string(),
+            }),
+        }
+    }
+
     fn visit_grouping_expr(&mut self, expr: &Expr) -> Result<(), SemanticAnalyserError> {
         match expr {
             Expr::Grouping { expression } => {
@@ -220,6 +401,33 @@ impl expr::ExprVisitor<Result<(), SemanticAnalyserError>> for SemanticAnalyser {
         }
     }
 
+    fn visit_indexassign_expr(&mut self, expr: &Expr) -> Result<(), SemanticAnalyserError> {
+        match expr {
+            Expr::IndexAssign { list, index, value } => {
+                value.accept_expr(self)?;
+                index.accept_expr(self)?;
+                Expr::Var { name: list.clone() }.accept_expr(self)?;
+                return self.check_mutable(list);
+            }
+            _ => return Err(SemanticAnalyserError::DifferentExpression {
+                expr: expr.clone(),
+                expected: "index assign".to_string(),
+            }),
+        }
+    }
+
+    fn visit_lambda_expr(&mut self, expr: &Expr) -> Result<(), SemanticAnalyserError> {
+        match expr {
+            Expr::Lambda { keyword: _, params, body } => {
+                return self.resolve_function(params, body, FunctionType::Function);
+            },
+            _ => return Err(SemanticAnalyserError::DifferentExpression {
+                expr: expr.clone(),
+                expected: "lambda".to_string(),
+            }),
+        }
+    }
+
     fn visit_list_expr(&mut self, expr: &Expr) -> Result<(), SemanticAnalyserError> {
         match expr {
             Expr::List { items } => {
@@ -238,8 +446,36 @@ impl expr::ExprVisitor<Result<(), SemanticAnalyserError>> for SemanticAnalyser {
 
     fn visit_listmethodcall_expr(&mut self, expr: &Expr) -> Result<(), SemanticAnalyserError> {
         match expr {
-            Expr::ListMethodCall { object, call } => {
+            Expr::ListMethodCall { object, call, optional: _ } => {
                 Expr::Var { name: object.clone() }.accept_expr(self)?;
+
+                // The method name is looked up on the object rather than in scope, so only the
+                // arguments are resolved
+                if let Expr::Call { callee, arguments, keywords } = &**call {
+                    if let Expr::Var { name } = &**callee {
+                        if List::mutates(&name.lexeme) {
+                            self.check_mutable(object).map_err(|_| {
+                                SemanticAnalyserError::ConstantChangedByMethod {
+                                    name: object.lexeme.clone(),
+                                    method: name.lexeme.clone(),
+                                }
+                            })?;
+                        }
+                    }
+                    for argument in arguments {
+                        argument.accept_expr(self)?;
+                    }
+                    for (_, argument) in keywords {
+                        argument.accept_expr(self)?;
+                    }
+                    return Ok(());
+                }
+
+                // A plain name is a member of a module, which is also looked up on the object
+                if let Expr::Var { .. } = &**call {
+                    return Ok(());
+                }
+
                 call.accept_expr(self)?;
                 return Ok(());
             },
@@ -275,9 +511,50 @@ impl expr::ExprVisitor<Result<(), SemanticAnalyserError>> for SemanticAnalyser {
         }
     }
 
+    fn visit_methodcall_expr(&mut self, expr: &Expr) -> Result<(), SemanticAnalyserError> {
+        match expr {
+            Expr::MethodCall { object, call, optional: _ } => {
+                object.accept_expr(self)?;
+
+                // As with a method on a variable, only the arguments are resolved
+                if let Expr::Call { callee: _, arguments, keywords } = &**call {
+                    for argument in arguments {
+                        argument.accept_expr(self)?;
+                    }
+                    for (_, argument) in keywords {
+                        argument.accept_expr(self)?;
+                    }
+                }
+                return Ok(());
+            },
+            _ => return Err(SemanticAnalyserError::DifferentExpression {
+                expr: expr.clone(),
+                expected: "methodcall".to_string(),
+            }),
+        }
+    }
+
+    fn visit_range_expr(&mut self, expr: &Expr) -> Result<(), SemanticAnalyserError> {
+        match expr {
+            Expr::Range { start, operator: _, end, step } => {
+                start.accept_expr(self)?;
+                end.accept_expr(self)?;
+                if let Some(step) = step {
+                    step.accept_expr(self)?;
+                }
+
+                return Ok(());
+            }
+            _ => return Err(SemanticAnalyserError::DifferentExpression {
+                expr: expr.clone(),
+                expected: "range".to_string(),
+            }),
+        }
+    }
+
     fn visit_splice_expr(&mut self, expr: &Expr) -> Result<(), SemanticAnalyserError> {
         match expr {
-            Expr::Splice { list, is_splice: _, start, end } => {
+            Expr::Splice { list, is_splice: _, start, end, optional: _ } => {
                 let var = Expr::Var { name: list.clone() };
                 var.accept_expr(self)?;
                 if let Some(start) = start {
@@ -316,10 +593,6 @@ impl expr::ExprVisitor<Result<(), SemanticAnalyserError>> for SemanticAnalyser {
                     return Ok(());
                 }
 
-                if name.lexeme == "hash" || name.lexeme == "clock" {
-                    return Ok(());
-                }
-
                 return Err(SemanticAnalyserError::VariableNotFound {
                     name: name.lexeme.clone(),
                 });
@@ -355,6 +628,47 @@ impl stmt::StmtVisitor<Result<(), SemanticAnalyserError>> for SemanticAnalyser {
         }
     }
     
+    fn visit_destructure_stmt(&mut self, stmt: &Stmt) -> Result<(), SemanticAnalyserError> {
+        match stmt {
+            Stmt::Destructure { pattern, initializer, constant } => {
+                initializer.accept_expr(self)?;
+                self.declare_pattern(pattern, !constant)?;
+
+                return Ok(());
+            }
+            _ => {
+                return Err(SemanticAnalyserError::DifferentStatement {
+                    stmt: stmt.clone(),
+                    expected: "destructure".to_string(),
+                });
+            }
+        }
+    }
+
+    fn visit_export_stmt(&mut self, stmt: &Stmt) -> Result<(), SemanticAnalyserError> {
+        match stmt {
+            Stmt::Export { declaration } => {
+                if self.curr != 0 {
+                    let name = match &**declaration {
+                        Stmt::Var { name, .. } | Stmt::Function { name, .. } => name.lexeme.clone(),
+                        Stmt::Destructure { pattern, .. } => pattern.to_string(),
+                        other => other.to_string(),
+                    };
+                    return Err(SemanticAnalyserError::ExportOutsideTopLevel { name });
+                }
+
+                declaration.accept_stmt(self)?;
+                return Ok(());
+            }
+            _ => {
+                return Err(SemanticAnalyserError::DifferentStatement {
+                    stmt: stmt.clone(),
+                    expected: "export".to_string(),
+                });
+            }
+        }
+    }
+
     fn visit_expression_stmt(&mut self, stmt: &Stmt) -> Result<(), SemanticAnalyserError> {
         match stmt {
             Stmt::Expression { expression } => {
@@ -399,6 +713,32 @@ impl stmt::StmtVisitor<Result<(), SemanticAnalyserError>> for SemanticAnalyser {
         }
     }
 
+    fn visit_forin_stmt(&mut self, stmt: &Stmt) -> Result<(), SemanticAnalyserError> {
+        match stmt {
+            Stmt::ForIn { name, iterable, body } => {
+                iterable.accept_expr(self)?;
+
+                // The loop variable lives in its own scope around the body, so it can shadow an
+                // existing variable with the same name
+                self.begin_scope();
+                let sym = Symbol::Ident {
+                    initialised: true,
+                    mutable: true,
+                };
+                self.symbol_tables[self.curr].insert(name.lexeme.clone(), sym);
+
+                body.accept_stmt(self)?;
+                self.end_scope();
+
+                return Ok(());
+            }
+            _ => return Err(SemanticAnalyserError::DifferentStatement {
+                stmt: stmt.clone(),
+                expected: "for in".to_string(),
+            }),
+        }
+    }
+
     fn visit_function_stmt(&mut self, stmt: &Stmt) -> Result<(), SemanticAnalyserError> {
         return self.pass_function(stmt, FunctionType::Function);
     }
@@ -423,10 +763,72 @@ impl stmt::StmtVisitor<Result<(), SemanticAnalyserError>> for SemanticAnalyser {
         }
     }
 
+    fn visit_import_stmt(&mut self, stmt: &Stmt) -> Result<(), SemanticAnalyserError> {
+        match stmt {
+            Stmt::Import { keyword: _, path: _, alias, names } => {
+                // Imported names are bound as constants, so they cannot be reassigned
+                for name in alias.iter().chain(names) {
+                    let sym = Symbol::Ident {
+                        initialised: true,
+                        mutable: false,
+                    };
+                    self.symbol_tables[self.curr].insert(name.lexeme.clone(), sym);
+                }
+
+                return Ok(());
+            }
+            _ => return Err(SemanticAnalyserError::DifferentStatement {
+                stmt: stmt.clone(),
+                expected: "import".to_string(),
+            }),
+        }
+    }
+
+    fn visit_match_stmt(&mut self, stmt: &Stmt) -> Result<(), SemanticAnalyserError> {
+        match stmt {
+            Stmt::Match { keyword, value, arms } => {
+                value.accept_expr(self)?;
+
+                for (i, arm) in arms.iter().enumerate() {
+                    // An arm can only be reached if no earlier unguarded arm matches everything
+                    // that it does
+                    let unreachable = arms[..i]
+                        .iter()
+                        .any(|earlier| earlier.guard.is_none() && earlier.pattern.covers(&arm.pattern));
+                    if unreachable {
+                        self.warnings.push(SemanticAnalyserWarning::UnreachableMatchArm {
+                            pattern: arm.pattern.to_string(),
+                            line: keyword.line,
+                        });
+                    }
+
+                    // The variables bound by the pattern live in their own scope around the
+                    // guard and body
+                    self.begin_scope();
+                    self.declare_pattern(&arm.pattern, true)?;
+
+                    if let Some(guard) = &arm.guard {
+                        guard.accept_expr(self)?;
+                    }
+                    arm.body.accept_stmt(self)?;
+                    self.end_scope();
+                }
+
+                return Ok(());
+            }
+            _ => return Err(SemanticAnalyserError::DifferentStatement {
+                stmt: stmt.clone(),
+                expected: "match".to_string(),
+            }),
+        }
+    }
+
     fn visit_print_stmt(&mut self, stmt: &Stmt) -> Result<(), SemanticAnalyserError> {
         match stmt {
-            Stmt::Print { expression } => {
-                expression.accept_expr(self)?;
+            Stmt::Print { keyword: _, expressions } => {
+                for expression in expressions {
+                    expression.accept_expr(self)?;
+                }
                 return Ok(());
             }
             _ => return Err(SemanticAnalyserError::DifferentStatement {
@@ -458,7 +860,7 @@ impl stmt::StmtVisitor<Result<(), SemanticAnalyserError>> for SemanticAnalyser {
 
     fn visit_var_stmt(&mut self, stmt: &Stmt) -> Result<(), SemanticAnalyserError> {
         match stmt {
-            Stmt::Var { name, initializer } => {
+            Stmt::Var { name, initializer, constant } => {
                 if self.check_defined(&name.lexeme) {
                     return Err(SemanticAnalyserError::VariableAlreadyAssignedInScope {
                         name: name.lexeme.clone(),
@@ -471,6 +873,7 @@ impl stmt::StmtVisitor<Result<(), SemanticAnalyserError>> for SemanticAnalyser {
 
                 let sym = Symbol::Ident {
                     initialised: initializer.is_some(),
+                    mutable: !constant,
                 };
                 self.symbol_tables[self.curr].insert(name.lexeme.clone(), sym);
 
@@ -501,4 +904,22 @@ impl stmt::StmtVisitor<Result<(), SemanticAnalyserError>> for SemanticAnalyser {
             }
         }
     }
+
+    fn visit_yield_stmt(&mut self, stmt: &Stmt) -> Result<(), SemanticAnalyserError> {
+        match stmt {
+            Stmt::Yield { keyword: _, value } => {
+                if self.func_type == FunctionType::None {
+                    return Err(SemanticAnalyserError::CannotYieldOutsideFunction);
+                }
+
+                value.accept_expr(self)?;
+
+                return Ok(());
+            }
+            _ => return Err(SemanticAnalyserError::DifferentStatement {
+                stmt: stmt.clone(),
+                expected: "yield".to_string(),
+            }),
+        }
+    }
 }
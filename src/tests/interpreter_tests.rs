@@ -1,12 +1,26 @@
+use std::{cell::{Cell, RefCell}, rc::Rc};
+
 use crate::{
+    convert::{FromValue, Rest},
     interpreter::Interpreter,
     lexer::Lexer,
+    output::{BufferOutput, CallbackOutput},
     parser::Parser,
+    run::run_in,
     semanticanalyser::SemanticAnalyser,
+    value::Value,
 };
 
 #[allow(unused)]
 pub fn run(source: &str) -> Vec<String> {
+    return run_with(&mut Interpreter::new(), source);
+}
+
+#[allow(unused)]
+pub fn run_with(interpreter: &mut Interpreter, source: &str) -> Vec<String> {
+    let output = BufferOutput::new();
+    interpreter.set_output(output.clone());
+
     let mut lexer = Lexer::new(source.to_string());
     let tokens = match lexer.run() {
         Ok(tokens) => tokens,
@@ -25,6 +39,8 @@ pub fn run(source: &str) -> Vec<String> {
     };
 
     let mut semantic_analyser = SemanticAnalyser::new(ast.clone());
+    semantic_analyser.declare_builtins(interpreter.builtins.borrow().names());
+    semantic_analyser.declare_globals(interpreter.globals.borrow().names());
     match semantic_analyser.run() {
         Ok(_) => {}
         Err(e) => {
@@ -32,9 +48,8 @@ pub fn run(source: &str) -> Vec<String> {
         }
     }
 
-    let mut interpreter = Interpreter::new();
     match interpreter.interpret(ast) {
-        Ok(output) => return output,
+        Ok(_) => return output.stdout(),
         Err(e) => {
             eprintln!("An interpreter error occured: {e}")
         }
@@ -249,4 +264,1604 @@ fn test_evaluations() {
             "3".to_string(),
         ]
     )
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_lambdas() {
+    assert_eq!(
+        run(
+            "
+            var double = def (x) { return x * 2; };
+            var add = (a, b) => a + b;
+            var answer = () => 42;
+            print double(4);
+            print add(1, 2);
+            print answer();
+            print ((x) => x - 1)(10);
+            "
+        ),
+        vec![
+            "8".to_string(),
+            "3".to_string(),
+            "42".to_string(),
+            "9".to_string(),
+        ]
+    );
+
+    assert_eq!(
+        run(
+            "
+            def apply(f, value) {
+                return f(value);
+            }
+            def makeAdder(n) {
+                return (x) => x + n;
+            }
+            var addTwo = makeAdder(2);
+            print apply(addTwo, 3);
+            print apply(def (x) { var y = x * x; return y; }, 5);
+            var grouped = (1 + 2) * 3;
+            print grouped;
+            "
+        ),
+        vec![
+            "5".to_string(),
+            "25".to_string(),
+            "9".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_higher_order_list_methods() {
+    assert_eq!(
+        run(
+            "
+            var nums = [3, 1, 4, 1, 5];
+            print nums.map((n) => n * 2);
+            print nums.filter((n) => n > 2);
+            print nums.reduce((acc, n) => acc + n);
+            print nums.reduce((acc, n) => acc + n, 100);
+            print nums.any((n) => n == 4);
+            print nums.all((n) => n > 1);
+            print nums.find((n) => n > 3);
+            print nums.find((n) => n > 10);
+            print nums.findIndex((n) => n == 1);
+            print nums.findIndex((n) => n == 9);
+            print nums.flatMap((n) => [n, n]);
+            print nums.sortBy((n) => -n);
+            print nums;
+
+            def show(n) {
+                print n;
+            }
+            var words = [\"pear\", \"fig\", \"apple\"];
+            var sorted = words.sortBy(def (w) { return w; });
+            sorted.forEach(show);
+            "
+        ),
+        vec![
+            "[6, 2, 8, 2, 10]".to_string(),
+            "[3, 4, 5]".to_string(),
+            "14".to_string(),
+            "114".to_string(),
+            "true".to_string(),
+            "false".to_string(),
+            "4".to_string(),
+            "null".to_string(),
+            "1".to_string(),
+            "-1".to_string(),
+            "[3, 3, 1, 1, 4, 4, 1, 1, 5, 5]".to_string(),
+            "[5, 4, 3, 1, 1]".to_string(),
+            "[3, 1, 4, 1, 5]".to_string(),
+            "apple".to_string(),
+            "fig".to_string(),
+            "pear".to_string(),
+        ]
+    );
+
+    assert_eq!(
+        run(
+            "
+            def show(n) {
+                print n;
+            }
+            var words = [\"pear\", \"fig\"];
+            words.forEach(show);
+            var hashes = words.map(hash);
+            print hashes.len();
+            "
+        ),
+        vec!["pear".to_string(), "fig".to_string(), "2".to_string()]
+    );
+
+    assert_eq!(run("var a = [1, 2]; a.map((n) => n + \"a\");"), vec!["error".to_string()]);
+    assert_eq!(run("var a = [1, 2]; a.map((x, y) => x);"), vec!["error".to_string()]);
+    assert_eq!(run("var a = [1, 2]; a.map(1);"), vec!["error".to_string()]);
+    assert_eq!(run("var a = []; a.reduce((x, y) => x);"), vec!["error".to_string()]);
+
+    // A sort that leaves the list as it was does not undo the comparator reassigning it
+    assert_eq!(
+        run(
+            "
+            var nums = [1, 2];
+            def compare(a, b) {
+                nums = [9];
+                return a - b;
+            }
+            nums.sort(compare);
+            print nums;
+            "
+        ),
+        vec!["[9]".to_string()]
+    );
+
+    let mut interpreter = Interpreter::new();
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    run_in(&mut interpreter, "var a = [1]; a.reduce();");
+    assert_eq!(
+        buffer.stderr(),
+        vec!["An interpreter error occured: 'reduce' expects 1 to 2 arguments but got 0"]
+    );
+}
+
+#[test]
+fn test_sorting() {
+    assert_eq!(
+        run(
+            "
+            var nums = [3, 1, 2];
+            var copy = nums.sorted();
+            print copy;
+            print nums;
+            nums.sort();
+            print nums;
+            nums.sort(true);
+            print nums;
+            nums.sort((a, b) => a - b);
+            print nums;
+            var words = [\"bb\", \"a\", \"ccc\", \"dd\"];
+            print words.sorted(null, (w) => hash(w) == hash(\"a\"));
+            print words.sorted(true);
+            var pairs = [[2, \"a\"], [1, \"b\"], [2, \"c\"], [1, \"d\"]];
+            print pairs.sorted(null, (p) => p[0]);
+            print pairs.sorted(null, (p) => p[0], true);
+            print pairs.sorted((a, b) => a[0] - b[0]);
+            var nested = [[2, 1], [1, 5], [2, 0]];
+            print nested.sorted();
+            var flags = [true, false, true];
+            print flags.sorted();
+            "
+        ),
+        vec![
+            "[1, 2, 3]".to_string(),
+            "[3, 1, 2]".to_string(),
+            "[1, 2, 3]".to_string(),
+            "[3, 2, 1]".to_string(),
+            "[1, 2, 3]".to_string(),
+            "[bb, ccc, dd, a]".to_string(),
+            "[dd, ccc, bb, a]".to_string(),
+            "[[1, b], [1, d], [2, a], [2, c]]".to_string(),
+            "[[2, a], [2, c], [1, b], [1, d]]".to_string(),
+            "[[1, b], [1, d], [2, a], [2, c]]".to_string(),
+            "[[1, 5], [2, 0], [2, 1]]".to_string(),
+            "[false, true, true]".to_string(),
+        ]
+    );
+
+    let mut source = "var items = [".to_string();
+    for i in 0..100 {
+        source.push_str(&format!("[{}, {i}], ", (i * 37) % 10));
+    }
+    source.push_str("]; items.sort(null, (p) => p[0]); print items;");
+    let mut expected: Vec<(i32, i32)> = (0..100).map(|i| ((i * 37) % 10, i)).collect();
+    expected.sort_by_key(|p| p.0);
+    let expected = expected.iter()
+        .map(|(k, i)| format!("[{k}, {i}]"))
+        .collect::<Vec<String>>()
+        .join(", ");
+    assert_eq!(run(&source), vec![format!("[{expected}]")]);
+
+    assert_eq!(run("var a = [1, \"a\"]; a.sort();"), vec!["error".to_string()]);
+    assert_eq!(run("var a = [1, 2]; a.sort((x, y) => \"a\");"), vec!["error".to_string()]);
+    assert_eq!(run("var a = [1, 2]; a.sort(1, 2, 3);"), vec!["error".to_string()]);
+}
+
+#[test]
+fn test_for_in() {
+    assert_eq!(
+        run(
+            "
+            var total = 0;
+            var items = [1, 2, 3];
+            for (item in items) {
+                total = total + item;
+            }
+            print total;
+            for (c in \"hi\") print c;
+            def countTo(n) {
+                var i = 0;
+                return () => {
+                    i++;
+                    if (i > n) return null;
+                    return i;
+                };
+            }
+            for (i in countTo(2)) print i;
+            def first(list) {
+                for (item in list) {
+                    if (item > 1) return item;
+                }
+                return null;
+            }
+            print first(items);
+            for (item in items) {
+                var copy = items;
+                copy.push(item);
+            }
+            items.push(4);
+            print items;
+            "
+        ),
+        vec![
+            "6".to_string(),
+            "h".to_string(),
+            "i".to_string(),
+            "1".to_string(),
+            "2".to_string(),
+            "2".to_string(),
+            "[1, 2, 3, 4]".to_string(),
+        ]
+    );
+
+    assert_eq!(
+        run("var items = [1, 2]; for (item in items) items.push(item);"),
+        vec!["error".to_string()]
+    );
+    assert_eq!(run("for (x in 5) print x;"), vec!["error".to_string()]);
+    assert_eq!(run("for (x in (a) => a) print x;"), vec!["error".to_string()]);
+}
+
+#[test]
+fn test_ranges() {
+    assert_eq!(
+        run(
+            "
+            var r = 0..5;
+            print r;
+            print r.len();
+            print r[2];
+            print r[1:3];
+            print r.toList();
+            var evens = 0..=10..2;
+            print evens.toList();
+            var down = 3..0;
+            print down.toList();
+            var empty = 0..10..-1;
+            print empty.len();
+            var total = 0;
+            for (i in 1..=4) total = total + i;
+            print total;
+            var items = [\"a\", \"b\", \"c\", \"d\"];
+            print items[1..3];
+            print items[3..=0..-2];
+            print evens[1..3];
+            "
+        ),
+        vec![
+            "0..5".to_string(),
+            "5".to_string(),
+            "2".to_string(),
+            "[1, 2, 3]".to_string(),
+            "[0, 1, 2, 3, 4]".to_string(),
+            "[0, 2, 4, 6, 8, 10]".to_string(),
+            "[3, 2, 1]".to_string(),
+            "0".to_string(),
+            "10".to_string(),
+            "[b, c]".to_string(),
+            "[d, b]".to_string(),
+            "[2, 4]".to_string(),
+        ]
+    );
+
+    assert_eq!(run("var r = 0..5; print r[5];"), vec!["error".to_string()]);
+    assert_eq!(run("var a = [1, 2]; print a[0..3];"), vec!["error".to_string()]);
+    assert_eq!(run("var r = 0..5..0;"), vec!["error".to_string()]);
+    assert_eq!(run("var r = 0..\"a\";"), vec!["error".to_string()]);
+
+    // Int ranges stay exact beyond the largest whole number a float can hold
+    assert_eq!(
+        run(
+            "
+            var big = 9007199254740993..=9007199254740995;
+            print big.toList();
+            var wide = -9223372036854775807..9223372036854775807;
+            print wide.len();
+            var huge = 0..=1e300;
+            print huge[2];
+            "
+        ),
+        vec![
+            "[9007199254740993, 9007199254740994, 9007199254740995]".to_string(),
+            "18446744073709551614".to_string(),
+            "2.0".to_string(),
+        ]
+    );
+
+    let mut interpreter = Interpreter::new();
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    run_in(&mut interpreter, "var r = 0..=1e300; print r.len();");
+    run_in(&mut interpreter, "print list(0..=1e300);");
+    assert_eq!(
+        buffer.stderr(),
+        vec![
+            "An interpreter error occured: A range has too many items to count",
+            "An interpreter error occured: A range has too many items to count",
+        ]
+    );
+}
+
+#[test]
+fn test_generators() {
+    assert_eq!(
+        run(
+            "
+            def naturals() {
+                var n = 0;
+                while (true) {
+                    n++;
+                    yield n;
+                }
+            }
+            var nums = naturals();
+            print nums.next();
+            print nums.next();
+            var squares = nums.map((n) => n * n);
+            var even = squares.filter((n) => n / 2 == 2 or n / 2 == 8 or n / 2 == 18);
+            var firstTwo = even.take(2);
+            print firstTwo.toList();
+            print nums.next();
+            def letters(word) {
+                for (c in word) {
+                    if (c == \"b\") return;
+                    yield c;
+                }
+            }
+            for (c in letters(\"abc\")) print c;
+            def pairs(n) {
+                for (var i = 0; i < n; i++) {
+                    var j = i * 10;
+                    yield [i, j];
+                }
+            }
+            var p = pairs(2);
+            print p.toList();
+            print p.next();
+            "
+        ),
+        vec![
+            "1".to_string(),
+            "2".to_string(),
+            "[16, 36]".to_string(),
+            "7".to_string(),
+            "a".to_string(),
+            "[[0, 0], [1, 10]]".to_string(),
+            "null".to_string(),
+        ]
+    );
+
+    assert_eq!(run("yield 1;"), vec!["error".to_string()]);
+    assert_eq!(
+        run("def gen() { yield 1; yield 2 / \"a\"; } var g = gen(); print g.next(); print g.next();"),
+        vec!["error".to_string()]
+    );
+    assert_eq!(
+        run("def gen() { yield g.next(); } var g = gen(); g.next();"),
+        vec!["error".to_string()]
+    );
+
+    // A list looped over inside a generator is locked while the generator runs, but not while
+    // it is suspended
+    assert_eq!(
+        run(
+            "
+            var ys = [1, 2];
+            def over() {
+                for (y in ys) yield y;
+            }
+            var o = over();
+            print o.next();
+            ys.push(3);
+            print o.next();
+            print ys;
+            "
+        ),
+        vec!["1".to_string(), "2".to_string(), "[1, 2, 3]".to_string()]
+    );
+    assert_eq!(
+        run("def each(items) { for (x in items) { items.push(x); yield x; } } var e = each([1]); e.next();"),
+        vec!["error".to_string()]
+    );
+}
+
+#[test]
+fn test_method_chaining() {
+    assert_eq!(
+        run(
+            "
+            def naturals() {
+                var n = 0;
+                while (true) {
+                    n++;
+                    yield n;
+                }
+            }
+            var g = naturals();
+            print g.map((n) => n * n).filter((n) => n % 2 == 1).take(3).toList();
+            var xs = [3, 1, 2];
+            print xs.sorted().map((n) => n * 10);
+            print [1, 2, 3].len();
+            print xs.copy().pop();
+            print xs;
+            var maybe = null;
+            print maybe?.copy().len();
+            "
+        ),
+        vec![
+            "[1, 9, 25]".to_string(),
+            "[10, 20, 30]".to_string(),
+            "3".to_string(),
+            "2".to_string(),
+            "[3, 1, 2]".to_string(),
+            "null".to_string(),
+        ]
+    );
+
+    assert_eq!(run("var xs = [1]; print xs.copy().1;"), vec!["error".to_string()]);
+}
+
+#[test]
+fn test_operators() {
+    assert_eq!(
+        run(
+            "
+            print 7 % 3;
+            print -7 % 3;
+            print 2 ** 10;
+            print -2 ** 2;
+            print 2 ** 3 ** 2;
+            print 7 // 2;
+            print -7 // 2;
+            print 6 & 3;
+            print 6 | 3;
+            print 6 ^ 3;
+            print ~5;
+            print 1 << 4;
+            print -16 >> 2;
+            print 1 + 2 * 3 % 4;
+            print 1 | 2 == 3;
+            print 1 << 2 + 1;
+            "
+        ),
+        vec![
+            "1", "-1", "1024", "-4", "512", "3", "-4", "2", "7", "5", "-6", "16", "-4", "3", "true",
+            "8",
+        ]
+    );
+
+    assert_eq!(run("print 1 / 0;"), vec!["error".to_string()]);
+    assert_eq!(run("print 1 % 0;"), vec!["error".to_string()]);
+    assert_eq!(run("print 1 // 0;"), vec!["error".to_string()]);
+    assert_eq!(run("print 1.5 & 1;"), vec!["error".to_string()]);
+    assert_eq!(run("print 1 << 64;"), vec!["error".to_string()]);
+
+    let mut interpreter = Interpreter::new();
+    interpreter.options.checked_division = false;
+    assert_eq!(
+        run_with(&mut interpreter, "print 1 / 0; print 0 % 0;"),
+        vec!["inf".to_string(), "NaN".to_string()]
+    );
+}
+
+#[test]
+fn test_compound_assignment() {
+    assert_eq!(
+        run(
+            "
+            var a = 5;
+            print a++;
+            print a;
+            print ++a;
+            print a--;
+            print --a;
+            a += 10;
+            print a;
+            a -= 2;
+            a *= 3;
+            a /= 4;
+            print a;
+            a %= 4;
+            print a;
+            print a += 1;
+            var items = [1, 2, 3];
+            items[0] = 10;
+            items[1] += 5;
+            print items[2]++;
+            print ++items[2];
+            print items;
+            var i = 0;
+            items[i++] *= 2;
+            print i;
+            print items;
+            "
+        ),
+        vec![
+            "5", "6", "7", "7", "5", "15", "9.75", "1.75", "2.75", "3", "5", "[10, 7, 5]", "1",
+            "[20, 7, 5]",
+        ]
+    );
+
+    assert_eq!(run("var a = 1; (a) += 1;"), vec!["error".to_string()]);
+    assert_eq!(run("var a = 1; 1++;"), vec!["error".to_string()]);
+    assert_eq!(run("var a = [1]; a[1] = 2;"), vec!["error".to_string()]);
+    assert_eq!(run("var a = [1]; a[0:0] += 2;"), vec!["error".to_string()]);
+    assert_eq!(run("var a = \"s\"; a++;"), vec!["error".to_string()]);
+    assert_eq!(
+        run("var a = [1, 2]; for (x in a) a[0] = x;"),
+        vec!["error".to_string()]
+    );
+}
+
+#[test]
+fn test_integers() {
+    assert_eq!(
+        run(
+            "
+            print 9223372036854775807 + 1;
+            print -9223372036854775807 - 2;
+            print 2 ** 100;
+            print 2 ** 100 - 2 ** 100 + 5;
+            print 12345678901234567890 * 98765432109876543210;
+            print 99999999999999999999 // 7;
+            print -(2 ** 64) % 7;
+            var n = 9007199254740992;
+            n++;
+            print n;
+            print 7 / 2;
+            print 2 ** -1;
+            print 1 == 1.0;
+            print 3 < 3.5;
+            print 2 ** 64 > 1.5;
+            var items = [1, 2, 3];
+            var r = 0..3;
+            print items[r[2]];
+            print items.sum();
+            "
+        ),
+        vec![
+            "9223372036854775808",
+            "-9223372036854775809",
+            "1267650600228229401496703205376",
+            "5",
+            "1219326311370217952237463801111263526900",
+            "14285714285714285714",
+            "-2",
+            "9007199254740993",
+            "3.5",
+            "0.5",
+            "true",
+            "true",
+            "true",
+            "3",
+            "6",
+        ]
+    );
+
+    assert_eq!(run("var a = [1, 2]; print a[1.0];"), vec!["error".to_string()]);
+    assert_eq!(run("var a = [1, 2]; print a[2 ** 64];"), vec!["error".to_string()]);
+    assert_eq!(run("var a = [1, 2]; a.remove(1.0);"), vec!["error".to_string()]);
+
+    // Powers too large to work out are an error, except for bases that never grow
+    assert_eq!(run("print 2 ** 9223372036854775807;"), vec!["error".to_string()]);
+    assert_eq!(run("print math.pow(2, 9223372036854775807);"), vec!["error".to_string()]);
+    assert_eq!(
+        run("print (-1) ** 9223372036854775807; print 0 ** 9223372036854775807;"),
+        vec!["-1".to_string(), "0".to_string()]
+    );
+}
+
+#[test]
+fn test_number_literals() {
+    assert_eq!(
+        run("print 0xFF + 0b1; print 0o777; print 1e3; print 2.5e-3; print 1_000 * 2; print 0xFFFFFFFFFFFFFFFF;"),
+        vec!["256", "511", "1000.0", "0.0025", "2000", "18446744073709551615"]
+    );
+
+    // Whole floats keep their `.0`, so they can be told apart from ints
+    assert_eq!(
+        run("print 1.0; print str(1.0); print 4 / 2; print [1.0, 2]; print -0.5 * 2; print 1e20;"),
+        vec!["1.0", "1.0", "2.0", "[1.0, 2]", "-1.0", "1e20"]
+    );
+    assert_eq!(run("print 0b;"), vec!["error".to_string()]);
+}
+
+#[test]
+fn test_conditional_expressions() {
+    assert_eq!(
+        run(
+            "
+            var n = 5;
+            print n > 3 ? \"big\" : \"small\";
+            print n > 9 ? \"huge\" : n > 3 ? \"big\" : \"small\";
+            print n < 3 ? 1 : 0.5;
+            var missing = null;
+            print missing ?? \"default\";
+            print n ?? \"default\";
+            print false ?? true;
+            print missing ?? null ?? 3;
+            var calls = 0;
+            def touch() { calls++; return 1; }
+            print n ?? touch();
+            print true ? 1 : touch();
+            print calls;
+            var items = [1, 2, 3];
+            print items?.len();
+            print missing?.len();
+            print missing?.push(touch());
+            print items?[1];
+            print missing?[touch()];
+            print calls;
+            var x = missing ?? items;
+            x[0] = n > 0 ? 10 : 20;
+            print x;
+            "
+        ),
+        vec![
+            "big", "big", "0.5", "default", "5", "false", "3", "5", "1", "0", "3", "null", "null",
+            "2", "null", "0", "[10, 2, 3]",
+        ]
+    );
+
+    assert_eq!(run("print true ? 1;"), vec!["error".to_string()]);
+    assert_eq!(run("var a = null; a?[0] = 1;"), vec!["error".to_string()]);
+
+    // `?[` and `?.` written straight after a value can still start a conditional
+    assert_eq!(
+        run(
+            "
+            var c = true;
+            var xs = [5, 6];
+            var n = null;
+            print c ?[1] : [2];
+            print c ?.5 : 1;
+            print c ? xs?[0] : 2;
+            print c ? n?[0] ?? 7 : 8;
+            print xs[n?[0] ?? 1:1];
+            print [c ?[1] : c ?[2] : [3]];
+            "
+        ),
+        vec!["[1]", "0.5", "5", "7", "[6]", "[[1]]"]
+    );
+}
+
+#[test]
+fn test_match() {
+    assert_eq!(
+        run(
+            "
+            def describe(value) {
+                match (value) {
+                    1 | 2 => return \"small\";
+                    -1 => return \"negative one\";
+                    \"hi\" => return \"greeting\";
+                    [] => return \"empty\";
+                    [x] => return [x];
+                    [first, ...rest] => return rest;
+                    null => return \"nothing\";
+                    n if n > 10 => return n * 2;
+                    _ => return \"other\";
+                }
+            }
+            print describe(1);
+            print describe(2.0);
+            print describe(-1);
+            print describe(\"hi\");
+            print describe([]);
+            print describe([\"a\"]);
+            print describe([1, 2, 3]);
+            print describe(null);
+            print describe(50);
+            print describe(5);
+            "
+        ),
+        vec![
+            "small", "small", "negative one", "greeting", "empty", "[a]", "[2, 3]", "nothing",
+            "100", "other",
+        ]
+    );
+
+    assert_eq!(
+        run(
+            "
+            var total = 0;
+            for (pair in [[1, 2], [3, 4], [5]]) {
+                match (pair) {
+                    [a, b] | [a, b, _] => { total += a * b; },
+                    [[_, ...], ...] => print \"nested\";
+                    [...] => print pair;
+                }
+            }
+            print total;
+            match (3) { 4 => print \"no match\"; }
+            var x = \"outer\";
+            match ([\"inner\"]) { [x] => print x; }
+            print x;
+            def gen() {
+                for (i in 0..4) {
+                    match (i % 2) {
+                        0 => yield i;
+                        _ => yield -i;
+                    }
+                }
+            }
+            var g = gen();
+            print g.toList();
+            "
+        ),
+        vec!["[5]", "14", "inner", "outer", "[0, -1, 2, -3]"]
+    );
+
+    assert_eq!(run("match (1) { x => print y; }"), vec!["error".to_string()]);
+    assert_eq!(run("match 1 { _ => print 1; }"), vec!["error".to_string()]);
+    assert_eq!(run("match (1) { + => print 1; }"), vec!["error".to_string()]);
+
+    let mut interpreter = Interpreter::new();
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    run_in(&mut interpreter, "match (1) { n => print n; 2 => print 2; }");
+    run_in(&mut interpreter, "match ([1]) { [a] | [a, b] => print a; }");
+    assert_eq!(buffer.stdout(), vec!["1".to_string()]);
+    assert_eq!(buffer.stderr().len(), 2);
+    assert!(buffer.stderr()[0].starts_with("Warning: The arm '2'"));
+    assert!(buffer.stderr()[1].starts_with("A semantic error occured"));
+}
+
+#[test]
+fn test_destructuring() {
+    assert_eq!(
+        run(
+            "
+            var [a, b, ...rest] = [1, 2, 3, 4];
+            print a;
+            print b;
+            print rest;
+            var [x, [y, _], ...] = [\"x\", [\"y\", \"z\"], 5, 6];
+            print [x, y];
+            [a, b] = [b, a];
+            print [a, b];
+            [a, [b, _]] = [10, [20, 30]];
+            print a + b;
+            var [only] = [[1, 2]];
+            print only;
+            var [first, ...empty] = [1];
+            print empty;
+            {
+                var [a] = [\"shadowed\"];
+                print a;
+            }
+            print a;
+            "
+        ),
+        vec!["1", "2", "[3, 4]", "[x, y]", "[2, 1]", "30", "[1, 2]", "[]", "shadowed", "10"]
+    );
+
+    assert_eq!(run("var [a, b];"), vec!["error".to_string()]);
+    assert_eq!(run("var [a, b] = [1, 2, 3];"), vec!["error".to_string()]);
+    assert_eq!(run("var [a, b] = 1;"), vec!["error".to_string()]);
+    assert_eq!(run("[a, b] = [1, 2];"), vec!["error".to_string()]);
+    assert_eq!(run("var a = 1; [a, 2] = [1, 2];"), vec!["error".to_string()]);
+
+    let mut interpreter = Interpreter::new();
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    run_in(&mut interpreter, "var [a, [b, c]] = [1, [2]];");
+    run_in(&mut interpreter, "var [d, d] = [1, 2];");
+    assert_eq!(
+        buffer.stderr()[0],
+        "An interpreter error occured: Cannot destructure value[1]: expected a list of 2 items, got a list of 1 items"
+    );
+    assert!(buffer.stderr()[1].starts_with("A semantic error occured"));
+}
+
+#[test]
+fn test_function_parameters() {
+    assert_eq!(
+        run(
+            "
+            def f(a, b = 2, ...rest) {
+                return [a, b, rest];
+            }
+            print f(1);
+            print f(1, 3);
+            print f(1, 3, 4, 5);
+            print f(1, b: 10);
+            print f(b: 10, a: 0);
+            def scale(x, factor = x * 2) { return x * factor; }
+            print scale(3);
+            print scale(3, 1);
+            var calls = 0;
+            def count() { calls++; return calls; }
+            def g(n = count()) { return n; }
+            print g();
+            print g(7);
+            print g();
+            var add = (a, b = 1) => a + b;
+            print add(1);
+            print add(1, b: 5);
+            var all = def (...items) { return items; };
+            print all();
+            print all(1, 2);
+            var nums = [1, 2];
+            print nums.map((n) => n * 2);
+            "
+        ),
+        vec![
+            "[1, 2, []]", "[1, 3, []]", "[1, 3, [4, 5]]", "[1, 10, []]", "[0, 10, []]", "18", "3",
+            "1", "7", "2", "2", "6", "[]", "[1, 2]", "[2, 4]",
+        ]
+    );
+
+    let mut interpreter = Interpreter::new();
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    run_in(&mut interpreter, "def f(a, b = 2) {} f();");
+    run_in(&mut interpreter, "def g(a, ...rest) {} g();");
+    run_in(&mut interpreter, "def h(a) {} h(1, 2);");
+    run_in(&mut interpreter, "def i(a) {} i(b: 1);");
+    run_in(&mut interpreter, "def j(a) {} j(1, a: 2);");
+    run_in(&mut interpreter, "def k(a, b = 2) {} k(b: 3);");
+    assert_eq!(
+        buffer.stderr(),
+        vec![
+            "An interpreter error occured: 'f' expects 1 to 2 arguments but got 0",
+            "An interpreter error occured: 'g' expects at least 1 argument but got 0",
+            "An interpreter error occured: 'h' expects 1 argument but got 2",
+            "An interpreter error occured: 'i' does not have a parameter called 'b'",
+            "An interpreter error occured: The argument 'a' of 'j' was given more than once",
+            "An interpreter error occured: 'k' is missing the argument 'a'",
+        ]
+    );
+
+    assert_eq!(run("def f(a = 1, b) {}"), vec!["error".to_string()]);
+    assert_eq!(run("def f(...a, b) {}"), vec!["error".to_string()]);
+    assert_eq!(run("def f(a, b) {} f(a: 1, 2);"), vec!["error".to_string()]);
+    assert_eq!(run("var items = [1]; items.push(item: 2);"), vec!["error".to_string()]);
+}
+
+#[test]
+fn test_constants() {
+    assert_eq!(
+        run(
+            "
+            const limit = 3;
+            const [low, high] = [1, 10];
+            print limit + low + high;
+            {
+                var limit = 5;
+                limit += 1;
+                print limit;
+            }
+            print limit;
+            def atLimit(n) { return n == limit; }
+            print atLimit(3);
+            const items = [1, 2];
+            print items[0];
+            "
+        ),
+        vec!["14", "6", "3", "true", "1"]
+    );
+
+    assert_eq!(run("const x;"), vec!["error".to_string()]);
+
+    let mut interpreter = Interpreter::new();
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    let rejected = [
+        "const a = 1; a = 2; print a;",
+        "const b = 1; b++; print b;",
+        "const c = 1; c *= 2; print c;",
+        "const d = [1]; d[0] = 2; print d;",
+        "const [e] = [1]; var f = 2; [e, f] = [f, e]; print e;",
+        "const g = 1; def h() { g = 2; } print g;",
+    ];
+    for source in rejected {
+        run_in(&mut interpreter, source);
+    }
+    assert!(buffer.stdout().is_empty());
+    assert_eq!(buffer.stderr().len(), rejected.len());
+    for error in buffer.stderr() {
+        assert!(error.starts_with("A semantic error occured: Cannot assign to"));
+    }
+
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    run_in(&mut interpreter, "const xs = [3, 1]; xs.push(2);");
+    run_in(&mut interpreter, "const ys = [3, 1]; def f() { ys.sort(); }");
+    run_in(&mut interpreter, "const zs = [3, 1]; print zs.len(); print zs.sorted();");
+    assert_eq!(buffer.stdout(), vec!["2".to_string(), "[1, 3]".to_string()]);
+    assert_eq!(
+        buffer.stderr(),
+        vec![
+            "A semantic error occured: Cannot call push on xs, as it is a constant",
+            "A semantic error occured: Cannot call sort on ys, as it is a constant",
+        ]
+    );
+
+    // Constants declared by an earlier run are only known to the interpreter
+    let mut interpreter = Interpreter::new();
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    run_in(&mut interpreter, "const total = 1; const list = [1];");
+    run_in(&mut interpreter, "total = 2;");
+    run_in(&mut interpreter, "list.push(2);");
+    run_in(&mut interpreter, "print total; print list;");
+    assert_eq!(buffer.stdout(), vec!["1".to_string(), "[1]".to_string()]);
+    assert_eq!(
+        buffer.stderr(),
+        vec![
+            "An interpreter error occured: Cannot assign to total on line 1, as it is a constant",
+            "An interpreter error occured: Cannot assign to list on line 1, as it is a constant",
+        ]
+    );
+}
+
+/// A temporary directory of module files, which is removed when it is dropped
+struct ModuleDir(std::path::PathBuf);
+
+impl std::ops::Deref for ModuleDir {
+    type Target = std::path::Path;
+
+    fn deref(&self) -> &Self::Target {
+        return &self.0;
+    }
+}
+
+impl Drop for ModuleDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Writes each file into a new directory under the system's temporary directory, for tests that
+/// import modules
+fn module_dir(name: &str, files: &[(&str, &str)]) -> ModuleDir {
+    let dir = std::env::temp_dir().join(format!("interpreter_v1_{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("lib")).unwrap();
+    for (file, source) in files {
+        std::fs::write(dir.join(file), source).unwrap();
+    }
+    return ModuleDir(dir);
+}
+
+#[test]
+fn test_modules() {
+    let dir = module_dir(
+        "modules",
+        &[
+            (
+                "lib/math_utils.ivl",
+                "print \"loading\";
+                export const pi = 3;
+                export def square(x) { return x * x; }
+                export def scale(x, by = 2) { return x * by; }
+                export var [low, high] = [1, 10];
+                var hidden = 5;
+                def area(r) { return pi * square(r); }
+                export var circle = area;",
+            ),
+            ("lib/counter.ivl", "from \"math_utils.ivl\" import square; export var nine = square(3);"),
+        ],
+    );
+
+    let mut interpreter = Interpreter::new();
+    interpreter.options.module_paths.push(dir.join("lib"));
+    assert_eq!(
+        run_with(
+            &mut interpreter,
+            "
+            import \"math_utils.ivl\" as mu;
+            from \"math_utils.ivl\" import square, pi;
+            from \"counter.ivl\" import nine;
+            print mu.square(4);
+            print mu.scale(3, by: 5);
+            print mu.pi + pi;
+            print square(5);
+            print mu.high - mu.low;
+            print mu.circle(2);
+            var f = mu.square;
+            print f(6);
+            print nine;
+            "
+        ),
+        vec!["loading", "16", "15", "6", "25", "9", "12", "36", "9"]
+    );
+
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    run_in(&mut interpreter, "print mu.hidden;");
+    run_in(&mut interpreter, "from \"math_utils.ivl\" import area;");
+    run_in(&mut interpreter, "import \"missing.ivl\" as m;");
+    run_in(&mut interpreter, "mu = 1;");
+    run_in(&mut interpreter, "def g() { export var x = 1; }");
+    assert!(buffer.stdout().is_empty());
+    assert_eq!(
+        buffer.stderr(),
+        vec![
+            "An interpreter error occured: The module 'math_utils.ivl' does not export 'hidden'",
+            "An interpreter error occured: The module 'math_utils.ivl' does not export 'area'",
+            "An interpreter error occured: Could not find the module 'missing.ivl' imported on line 1",
+            "An interpreter error occured: Cannot assign to mu on line 1, as it is a constant",
+            "A semantic error occured: x can only be exported at the top level of a file",
+        ]
+    );
+}
+
+#[test]
+fn test_module_errors() {
+    let dir = module_dir(
+        "module_errors",
+        &[
+            ("a.ivl", "import \"b.ivl\" as b;"),
+            ("b.ivl", "import \"a.ivl\" as a;"),
+            ("runtime.ivl", "var a = 1;\n\nvar b = a / 0;"),
+            ("nested.ivl", "var x = 1;\nimport \"runtime.ivl\" as r;"),
+            ("syntax.ivl", "var = 1;"),
+            ("funcs.ivl", "export def f(x) {\n    return x / 0;\n}\nexport var g = (x) => x.missing();"),
+        ],
+    );
+
+    let mut interpreter = Interpreter::new();
+    interpreter.options.module_paths.push(dir.to_path_buf());
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    run_in(&mut interpreter, "import \"a.ivl\" as a;");
+    run_in(&mut interpreter, "import \"runtime.ivl\" as r;");
+    run_in(&mut interpreter, "import \"nested.ivl\" as n;");
+    run_in(&mut interpreter, "import \"syntax.ivl\" as s;");
+    run_in(&mut interpreter, "import \"funcs.ivl\" as m; m.f(1);");
+    run_in(&mut interpreter, "from \"funcs.ivl\" import g; g([1]);");
+
+    let errors = buffer.stderr();
+    assert_eq!(errors.len(), 6);
+    assert!(errors[0].contains("Modules cannot import each other in a cycle: a.ivl -> b.ivl -> a.ivl"));
+    assert!(errors[1].ends_with("(in the module 'runtime.ivl' on line 3)"));
+    assert!(errors[2].ends_with("(in the module 'runtime.ivl' on line 3)"));
+    assert!(errors[3].starts_with("An interpreter error occured: Could not load the module 'syntax.ivl'"));
+    assert_eq!(
+        errors[4],
+        "An interpreter error occured: Cannot divide by zero (in the module 'funcs.ivl' on line 2)"
+    );
+    assert!(errors[5].ends_with("(in the module 'funcs.ivl' on line 4)"));
+}
+
+#[test]
+fn test_math_module() {
+    assert_eq!(
+        run(
+            "
+            print math.sqrt(16);
+            print math.pow(2, 10);
+            print math.pow(2, 0.5) == math.sqrt(2);
+            print math.abs(-3);
+            print math.abs(-2.5);
+            print math.floor(2.7);
+            print math.ceil(-2.7);
+            print math.round(2.5);
+            print math.floor(7);
+            print math.min(3, 1.5, 2);
+            print math.max(3, 1.5, 2);
+            print math.max(4);
+            print math.round(math.sin(math.pi / 2));
+            print math.cos(0);
+            print math.atan2(0, 1);
+            print math.log(math.e);
+            print math.log(8, base: 2);
+            print math.log10(1000);
+            print math.isNaN(math.sqrt(-1));
+            print math.isFinite(1);
+            print [math.isInt(2), math.isInt(2.0), math.isInt(2 ** 100)];
+            var sqrt = math.sqrt;
+            print sqrt(9);
+            "
+        ),
+        vec![
+            "4.0", "1024", "true", "3", "2.5", "2", "-2", "3", "7", "1.5", "3", "4", "1", "1.0", "0.0",
+            "1.0", "3.0", "3.0", "true", "true", "[true, false, true]", "3.0"
+        ]
+    );
+
+    let mut interpreter = Interpreter::new();
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    run_in(&mut interpreter, "print math.sqrt(\"4\");");
+    run_in(&mut interpreter, "print math.max();");
+    run_in(&mut interpreter, "print math.tau;");
+    run_in(&mut interpreter, "print sqrt(4);");
+    assert!(buffer.stdout().is_empty());
+    assert_eq!(
+        buffer.stderr(),
+        vec![
+            "An interpreter error occured: Expected parameter 'x' of 'math.sqrt' to be num, got str",
+            "An interpreter error occured: 'math.max' expects at least 1 argument but got 0",
+            "An interpreter error occured: The module 'math' does not export 'tau'",
+            "A semantic error occured: Couldn't find variable sqrt",
+        ]
+    );
+}
+
+#[test]
+fn test_random() {
+    let source = "
+        var rolls = [];
+        for (var i = 0; i < 5; i++) { rolls.push(randomInt(1, 6)); }
+        print rolls;
+        print random();
+        print choice([\"a\", \"b\", \"c\"]);
+        print shuffle([1, 2, 3, 4, 5]);
+        print sample([1, 2, 3, 4, 5], 2);
+        ";
+
+    // The same seed gives the same numbers, whether it comes from the options or from `seed`
+    let mut interpreter = Interpreter::new();
+    interpreter.options.seed = Some(42);
+    let first = run_with(&mut interpreter, source);
+    let second = run_with(&mut Interpreter::new(), &format!("seed(42); {source}"));
+    assert_eq!(first, second);
+    assert_eq!(first.len(), 5);
+
+    let mut interpreter = Interpreter::new();
+    assert_eq!(
+        run_with(
+            &mut interpreter,
+            "
+            seed(7);
+            var valid = true;
+            for (var i = 0; i < 200; i++) {
+                var n = randomInt(-2, 2);
+                var f = random();
+                if (n < -2 or n > 2 or f < 0 or f >= 1) { valid = false; }
+            }
+            print valid;
+            print randomInt(3, 3);
+            var shuffled = shuffle([1, 2, 3, 4]);
+            shuffled.sort();
+            print shuffled;
+            var sampled = sample([1, 2, 3], 3);
+            sampled.sort();
+            print sampled;
+            print sample([1, 2, 3], 0);
+            var items = [1, 2, 3];
+            shuffle(items);
+            print items;
+            "
+        ),
+        vec!["true", "3", "[1, 2, 3, 4]", "[1, 2, 3]", "[]", "[1, 2, 3]"]
+    );
+
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    run_in(&mut interpreter, "print randomInt(5, 1);");
+    run_in(&mut interpreter, "print choice([]);");
+    run_in(&mut interpreter, "print sample([1, 2], 3);");
+    run_in(&mut interpreter, "print sample([1, 2], -1);");
+    run_in(&mut interpreter, "print randomInt(1.5, 2);");
+    assert!(buffer.stdout().is_empty());
+    assert_eq!(
+        buffer.stderr(),
+        vec![
+            "An interpreter error occured: Expected the low bound of 'randomInt' to be at most the high bound, got 5 and 1",
+            "An interpreter error occured: Cannot call 'choice' on an empty list",
+            "An interpreter error occured: Cannot take a sample of 3 items from a list of 2 items",
+            "An interpreter error occured: Cannot take a sample of a negative number of items, got -1",
+            "An interpreter error occured: Expected parameter 'low' of 'randomInt' to be whole num, got num",
+        ]
+    );
+}
+
+#[test]
+fn test_types() {
+    assert_eq!(
+        run(
+            "
+            def f() {}
+            def g() { yield 1; yield 2; }
+            print [type(1), type(1.5), type(\"a\"), type(true), type(null)];
+            print [type([]), type(f), type(clock), type(1..3), type(g()), type(math)];
+            print num(\"42\") + 1;
+            print num(\" -2.5 \");
+            print num(\"1e3\");
+            print num(\"123456789012345678901234567890\");
+            print num(true) + num(false);
+            print num(7);
+            print str(12) == \"12\";
+            print str([1, \"a\"]);
+            print [bool(0), bool(\"\"), bool([]), bool(null), bool(false), bool(f)];
+            print list(\"abc\");
+            print list(1..4);
+            print list(g());
+            if (f) { print \"functions are truthy\"; }
+            if ([]) { print \"lists are truthy\"; }
+            print !g();
+            "
+        ),
+        vec![
+            "[num, num, str, bool, null]",
+            "[list, function, native, range, generator, module]",
+            "43",
+            "-2.5",
+            "1000.0",
+            "123456789012345678901234567890",
+            "1",
+            "7",
+            "true",
+            "[1, a]",
+            "[true, true, true, false, false, true]",
+            "[a, b, c]",
+            "[1, 2, 3]",
+            "[1, 2]",
+            "functions are truthy",
+            "lists are truthy",
+            "false",
+        ]
+    );
+
+    let mut interpreter = Interpreter::new();
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    run_in(&mut interpreter, "print num(\"4x2\");");
+    run_in(&mut interpreter, "print num(\"inf\");");
+    run_in(&mut interpreter, "print num(null);");
+    run_in(&mut interpreter, "print num([1]);");
+    run_in(&mut interpreter, "print list(5);");
+    run_in(&mut interpreter, "str = 1;");
+    assert!(buffer.stdout().is_empty());
+    assert_eq!(
+        buffer.stderr(),
+        vec![
+            "An interpreter error occured: Cannot convert the str \"4x2\" to num",
+            "An interpreter error occured: Cannot convert the str \"inf\" to num",
+            "An interpreter error occured: Cannot convert null to num",
+            "An interpreter error occured: Cannot convert the list [1] to num",
+            "An interpreter error occured: Cannot convert the num 5 to list",
+            "A semantic error occured: Cannot assign to str, as it is a constant",
+        ]
+    );
+
+    // Builtins can be shadowed by the script's own variables
+    assert_eq!(
+        run("var list = [1]; def type() { return \"mine\"; } print list; print type();"),
+        vec!["[1]", "mine"]
+    );
+}
+
+#[test]
+fn test_print_and_format() {
+    assert_eq!(
+        run(
+            "
+            def f() {}
+            var name = \"box\";
+            var n = 3.14159;
+            print 1, \"a\", [2], null;
+            print f == f, clock;
+            print format(\"{} has {:.2} items\", name, n);
+            print format(\"{1} {0} {1}\", \"a\", \"b\");
+            print format(\"{name}: {n:.1}\", name: name, n: n);
+            print format(\"[{:5}] [{:<5}] [{:>5}] [{:^5}]\", 42, 42, \"ab\", \"ab\");
+            print format(\"[{:*^7}] [{:0>4}] [{:.3}]\", \"mid\", 7, \"abcdef\");
+            print format(\"{{literal}} {:.0}\", 2);
+            def show(x) { return format(\"x={x}\", x: x); }
+            print format(\"{greeting}, {}!\", \"Ada\", greeting: \"Hi\");
+            print show([1, 2]);
+            "
+        ),
+        vec![
+            "1 a [2] null",
+            "true <native clock/0>",
+            "box has 3.14 items",
+            "b a b",
+            "box: 3.1",
+            "[   42] [42   ] [   ab] [ ab  ]",
+            "[**mid**] [0007] [abc]",
+            "{literal} 2",
+            "Hi, Ada!",
+            "x=[1, 2]",
+        ]
+    );
+
+    let mut interpreter = Interpreter::new();
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    run_in(&mut interpreter, "print \"out\"; eprint \"warning:\", 3;");
+    run_in(&mut interpreter, "print format(\"{} {}\", 1);");
+    run_in(&mut interpreter, "var missing = 1; print format(\"{missing}\");");
+    run_in(&mut interpreter, "print format(\"{:x}\", 1);");
+    run_in(&mut interpreter, "print format(\"{\");");
+    run_in(&mut interpreter, "print format(\"}\");");
+    run_in(&mut interpreter, "print format(1);");
+    assert_eq!(buffer.stdout(), vec!["out"]);
+    assert_eq!(
+        buffer.stderr(),
+        vec![
+            "warning: 3",
+            "An interpreter error occured: Invalid format string \"{} {}\": there is no argument 1",
+            "An interpreter error occured: Invalid format string \"{missing}\": there is no argument called 'missing'",
+            "An interpreter error occured: Invalid format string \"{:x}\": 'x' is not a valid format spec",
+            "An interpreter error occured: Invalid format string \"{\": a '{' is never closed",
+            "An interpreter error occured: Invalid format string \"}\": a '}' outside a placeholder must be written as '}}'",
+            "An interpreter error occured: Expected parameter 'template' of 'format' to be str, got num",
+        ]
+    );
+}
+
+#[test]
+fn test_repr() {
+    assert_eq!(
+        run(
+            "
+            def add(a, b) { return a + b; }
+            def greet(name, greeting = \"hi\") {}
+            def total(...nums) {}
+            def g() { yield 1; }
+            print repr([\"1\", 2, 2.0, 2.5, true, null]);
+            print repr(1e20), repr([1e20]), str(1e20);
+            print str([\"1\", 2]);
+            print repr(\"a\tb\\c\");
+            print repr(add), repr(greet), repr(total), repr((x) => x);
+            print repr(clock), repr(math.max);
+            print add, clock;
+            print repr(g()), repr(math), repr(1..3);
+            var nested = [1];
+            for (var i = 0; i < 10; i++) { nested = [nested]; }
+            print repr(nested);
+            print repr(list(0..103));
+            "
+        ),
+        vec![
+            "[\"1\", 2, 2.0, 2.5, true, null]",
+            "1e20 [1e20] 1e20",
+            "[1, 2]",
+            "\"a\\tb\\\\c\"",
+            "<fn add/2> <fn greet/1..=2> <fn total/0..> <fn lambda/1>",
+            "<native clock/0> <native math.max/1..>",
+            "<fn add/2> <native clock/0>",
+            "<generator g> <module math> 1..3",
+            "[[[[[[[[[...]]]]]]]]]",
+            &format!(
+                "[{}, ... 3 more]",
+                (0..100).map(|i| i.to_string()).collect::<Vec<String>>().join(", ")
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_list_library() {
+    assert_eq!(
+        run(
+            "
+            var a = [1, 2, 3];
+            a.reverse();
+            print a;
+            print a.contains(2);
+            print a.contains(5);
+            a.extend([4, 4]);
+            print a;
+            print a.concat([5], [6, 7]);
+            print a.join(\", \");
+            print a.count(4);
+            print a.unique();
+            var nested = [a, [8, [9]]];
+            print nested.flatten();
+            var b = a.copy();
+            a.clear();
+            print a;
+            print b;
+            print b.min();
+            print b.max();
+            print b.sum();
+            var words = [\"b\", \"a\"];
+            print words.max();
+            b.insertAt(5, 0);
+            b.insertAt(0, -1);
+            print b.remove(6);
+            print b;
+            "
+        ),
+        vec![
+            "[3, 2, 1]".to_string(),
+            "true".to_string(),
+            "false".to_string(),
+            "[3, 2, 1, 4, 4]".to_string(),
+            "[3, 2, 1, 4, 4, 5, 6, 7]".to_string(),
+            "3, 2, 1, 4, 4".to_string(),
+            "2".to_string(),
+            "[3, 2, 1, 4]".to_string(),
+            "[3, 2, 1, 4, 4, 8, [9]]".to_string(),
+            "[]".to_string(),
+            "[3, 2, 1, 4, 4]".to_string(),
+            "1".to_string(),
+            "4".to_string(),
+            "14".to_string(),
+            "b".to_string(),
+            "0".to_string(),
+            "[-1, 3, 2, 1, 4, 4]".to_string(),
+        ]
+    );
+
+    assert_eq!(run("var a = [1]; a.remove(1);"), vec!["error".to_string()]);
+    assert_eq!(run("var a = [1]; a.remove(-1);"), vec!["error".to_string()]);
+    assert_eq!(run("var a = [1]; a.remove(0.5);"), vec!["error".to_string()]);
+    assert_eq!(run("var a = []; a.remove(0);"), vec!["error".to_string()]);
+    assert_eq!(run("var a = [1]; a.insertAt(2, 0);"), vec!["error".to_string()]);
+    assert_eq!(run("var a = [1]; a.reverse(1);"), vec!["error".to_string()]);
+    assert_eq!(run("var a = [1]; a.extend(2);"), vec!["error".to_string()]);
+    assert_eq!(run("var a = [1, \"a\"]; print a.sum();"), vec!["error".to_string()]);
+    assert_eq!(run("var a = []; print a.min();"), vec!["error".to_string()]);
+}
+
+#[test]
+fn test_native_functions() {
+    let mut interpreter = Interpreter::new();
+    let calls = Rc::new(Cell::new(0));
+    let counter = Rc::clone(&calls);
+    interpreter.register_fn("tick", &[], move || {
+        counter.set(counter.get() + 1);
+        counter.get() as f64
+    });
+    interpreter.register_fn("greet", &["name", "greeting"], |name: String, greeting: Option<String>| {
+        format!("{}, {name}", greeting.unwrap_or("Hello".to_string()))
+    });
+    interpreter.register_fn("total", &["nums"], |nums: Rest<f64>| {
+        nums.0.iter().fold(0.0, |acc, n| acc + n)
+    });
+    interpreter.register_fn("double", &["items"], |items: Vec<f64>| {
+        items.iter().map(|n| n * 2.0).collect::<Vec<f64>>()
+    });
+
+    assert_eq!(
+        run_with(
+            &mut interpreter,
+            "
+            tick();
+            print tick();
+            print greet(\"Ada\");
+            print greet(\"Ada\", \"Hi\");
+            print greet(greeting: \"Hey\", name: \"Ada\");
+            print total();
+            print total(1, 2, 3);
+            print double([1, 2]);
+            "
+        ),
+        vec![
+            "2.0".to_string(),
+            "Hello, Ada".to_string(),
+            "Hi, Ada".to_string(),
+            "Hey, Ada".to_string(),
+            "0.0".to_string(),
+            "6.0".to_string(),
+            "[2.0, 4.0]".to_string(),
+        ]
+    );
+    assert_eq!(calls.get(), 2);
+
+    assert_eq!(
+        run_with(&mut interpreter, "greet(1);"),
+        vec!["error".to_string()]
+    );
+
+    assert_eq!(
+        run_with(&mut interpreter, "greet();"),
+        vec!["error".to_string()]
+    );
+
+    assert_eq!(
+        run_with(&mut interpreter, "greet(greeting: \"Hi\");"),
+        vec!["error".to_string()]
+    );
+
+    assert_eq!(
+        run_with(&mut interpreter, "total(nums: 1);"),
+        vec!["error".to_string()]
+    );
+
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    run_in(&mut interpreter, "greet(greeting: \"Hi\");");
+    assert_eq!(
+        buffer.stderr(),
+        vec!["An interpreter error occured: 'greet' is missing the argument 'name'"]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Native function 'bad' has an optional parameter before a required one")]
+fn test_native_function_param_order() {
+    let mut interpreter = Interpreter::new();
+    interpreter.register_fn("bad", &["a", "b"], |a: Option<f64>, b: f64| a.unwrap_or(0.0) + b);
+}
+
+#[test]
+#[should_panic(expected = "Native function 'bad' has a rest parameter before a required one")]
+fn test_native_function_rest_not_last() {
+    let mut interpreter = Interpreter::new();
+    interpreter.register_fn("bad", &["a", "b"], |a: Rest<f64>, b: f64| a.0.len() as f64 + b);
+}
+
+#[test]
+fn test_host_globals() {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_global("prices", vec![2.5, 4.0, 1.5]);
+    interpreter.set_global("discount", 0.5);
+
+    assert_eq!(
+        run_with(
+            &mut interpreter,
+            "
+            var total = 0;
+            for (var i = 0; i < prices.len(); i++) {
+                total = total + prices[i];
+            }
+            def applyDiscount(amount) {
+                return amount * discount;
+            }
+            print total;
+            "
+        ),
+        vec!["8.0".to_string()]
+    );
+
+    let total = interpreter.get_global("total").unwrap();
+    assert_eq!(f64::from_value(total.clone()), Ok(8.0));
+    assert_eq!(interpreter.get_global("missing"), None);
+
+    let discounted = interpreter.call_function("applyDiscount", vec![total]).unwrap();
+    assert_eq!(discounted, Value::from(4.0));
+
+    let hashed = interpreter.call_function("hash", vec![Value::from("test")]).unwrap();
+    assert_eq!(
+        hashed,
+        Value::from("9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08")
+    );
+
+    assert!(interpreter.call_function("missing", vec![]).is_err());
+    assert!(interpreter.call_function("applyDiscount", vec![]).is_err());
+}
+
+#[test]
+fn test_output() {
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let errors = Rc::new(RefCell::new(Vec::new()));
+    let out = Rc::clone(&lines);
+    let err = Rc::clone(&errors);
+    let mut interpreter = Interpreter::with_output(CallbackOutput::new(
+        move |line| out.borrow_mut().push(line.to_string()),
+        move |line| err.borrow_mut().push(line.to_string()),
+    ));
+
+    run_in(&mut interpreter, "print 1; print [1, 2]; 1();");
+    assert_eq!(*lines.borrow(), vec!["1".to_string(), "[1, 2]".to_string()]);
+    assert_eq!(errors.borrow().len(), 1);
+    assert!(errors.borrow()[0].starts_with("An interpreter error occured"));
+
+    let buffer = BufferOutput::new();
+    interpreter.set_output(buffer.clone());
+    run_in(&mut interpreter, "print \"ok\";");
+    run_in(&mut interpreter, "print missing;");
+    assert_eq!(buffer.stdout(), vec!["ok".to_string()]);
+    assert_eq!(buffer.stderr().len(), 1);
+    assert!(buffer.stderr()[0].starts_with("A semantic error occured"));
+}
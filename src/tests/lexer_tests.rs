@@ -74,6 +74,122 @@ fn test_double_symbols() {
     );
 }
 
+#[test]
+fn test_arrow() {
+    assert_eq!(
+        lex("(x) => x"),
+        vec![
+            token!(LParen ; "(" ; "" ; 1 ; 0 ; 1),
+            token!(Identifier ; "x" ; "" ; 1 ; 1 ; 2),
+            token!(RParen ; ")" ; "" ; 1 ; 2 ; 3),
+            token!(Arrow ; "=>" ; "" ; 1 ; 4 ; 6),
+            token!(Identifier ; "x" ; "" ; 1 ; 7 ; 8),
+            token!(Eof ; "" ; "" ; 1 ; 8 ; 8),
+        ]
+    );
+}
+
+#[test]
+fn test_ranges() {
+    assert_eq!(
+        lex("1..5 0..=10..2"),
+        vec![
+            token!(Int ; "1" ; "1" ; 1 ; 0 ; 1),
+            token!(DotDot ; ".." ; "" ; 1 ; 1 ; 3),
+            token!(Int ; "5" ; "5" ; 1 ; 3 ; 4),
+            token!(Int ; "0" ; "0" ; 1 ; 5 ; 6),
+            token!(DotDotEqual ; "..=" ; "" ; 1 ; 6 ; 9),
+            token!(Int ; "10" ; "10" ; 1 ; 9 ; 11),
+            token!(DotDot ; ".." ; "" ; 1 ; 11 ; 13),
+            token!(Int ; "2" ; "2" ; 1 ; 13 ; 14),
+            token!(Eof ; "" ; "" ; 1 ; 14 ; 14),
+        ]
+    );
+}
+
+#[test]
+fn test_operators() {
+    assert_eq!(
+        lex("% ** // & | ^ ~ << >>"),
+        vec![
+            token!(Percent ; "%" ; "" ; 1 ; 0 ; 1),
+            token!(AsteriskAsterisk ; "**" ; "" ; 1 ; 2 ; 4),
+            token!(FSlashFSlash ; "//" ; "" ; 1 ; 5 ; 7),
+            token!(Ampersand ; "&" ; "" ; 1 ; 8 ; 9),
+            token!(Pipe ; "|" ; "" ; 1 ; 10 ; 11),
+            token!(Caret ; "^" ; "" ; 1 ; 12 ; 13),
+            token!(Tilde ; "~" ; "" ; 1 ; 14 ; 15),
+            token!(LessLess ; "<<" ; "" ; 1 ; 16 ; 18),
+            token!(GreaterGreater ; ">>" ; "" ; 1 ; 19 ; 21),
+            token!(Eof ; "" ; "" ; 1 ; 21 ; 21),
+        ]
+    );
+}
+
+#[test]
+fn test_compound_assignment() {
+    assert_eq!(
+        lex("+= -= *= /= %="),
+        vec![
+            token!(PlusEqual ; "+=" ; "" ; 1 ; 0 ; 2),
+            token!(MinusEqual ; "-=" ; "" ; 1 ; 3 ; 5),
+            token!(AsteriskEqual ; "*=" ; "" ; 1 ; 6 ; 8),
+            token!(FSlashEqual ; "/=" ; "" ; 1 ; 9 ; 11),
+            token!(PercentEqual ; "%=" ; "" ; 1 ; 12 ; 14),
+            token!(Eof ; "" ; "" ; 1 ; 14 ; 14),
+        ]
+    );
+}
+
+#[test]
+fn test_question_operators() {
+    assert_eq!(
+        lex("? ?? a?.b a?["),
+        vec![
+            token!(Question ; "?" ; "" ; 1 ; 0 ; 1),
+            token!(QuestionQuestion ; "??" ; "" ; 1 ; 2 ; 4),
+            token!(Identifier ; "a" ; "" ; 1 ; 5 ; 6),
+            token!(QuestionDot ; "?." ; "" ; 1 ; 6 ; 8),
+            token!(Identifier ; "b" ; "" ; 1 ; 8 ; 9),
+            token!(Identifier ; "a" ; "" ; 1 ; 10 ; 11),
+            token!(QuestionLBrack ; "?[" ; "" ; 1 ; 11 ; 13),
+            token!(Eof ; "" ; "" ; 1 ; 13 ; 13),
+        ]
+    );
+
+    // A '?' before a float like `.5` is never an optional access
+    assert_eq!(
+        lex("c ?.5 : 1"),
+        vec![
+            token!(Identifier ; "c" ; "" ; 1 ; 0 ; 1),
+            token!(Question ; "?" ; "" ; 1 ; 2 ; 3),
+            token!(Dot ; "." ; "" ; 1 ; 3 ; 4),
+            token!(Int ; "5" ; "5" ; 1 ; 4 ; 5),
+            token!(Colon ; ":" ; "" ; 1 ; 6 ; 7),
+            token!(Int ; "1" ; "1" ; 1 ; 8 ; 9),
+            token!(Eof ; "" ; "" ; 1 ; 9 ; 9),
+        ]
+    );
+}
+
+#[test]
+fn test_match_tokens() {
+    assert_eq!(
+        lex("match [a, ...rest] .."),
+        vec![
+            token!(Match ; "match" ; "" ; 1 ; 0 ; 5),
+            token!(LBrack ; "[" ; "" ; 1 ; 6 ; 7),
+            token!(Identifier ; "a" ; "" ; 1 ; 7 ; 8),
+            token!(Comma ; "," ; "" ; 1 ; 8 ; 9),
+            token!(DotDotDot ; "..." ; "" ; 1 ; 10 ; 13),
+            token!(Identifier ; "rest" ; "" ; 1 ; 13 ; 17),
+            token!(RBrack ; "]" ; "" ; 1 ; 17 ; 18),
+            token!(DotDot ; ".." ; "" ; 1 ; 19 ; 21),
+            token!(Eof ; "" ; "" ; 1 ; 21 ; 21),
+        ]
+    );
+}
+
 #[test]
 fn test_strings() {
     assert_eq!(
@@ -89,6 +205,8 @@ fn test_strings() {
         vec![]
     );
 
+    assert_eq!(lex("\"Unterminated\nover lines"), vec![]);
+
     assert_eq!(
         lex("\"New\n\rline\""),
         vec![
@@ -101,8 +219,18 @@ fn test_strings() {
 #[test]
 fn test_comments() {
     assert_eq!(
-        lex("// Comment body"),
+        lex("# Comment body"),
+        vec![
+        ]
+    );
+
+    assert_eq!(
+        lex("7 // 2 # Comment body\n"),
         vec![
+            token!(Int ; "7" ; "7" ; 1 ; 0 ; 1),
+            token!(FSlashFSlash ; "//" ; "" ; 1 ; 2 ; 4),
+            token!(Int ; "2" ; "2" ; 1 ; 5 ; 6),
+            token!(Eof ; "" ; "" ; 2 ; 22 ; 22),
         ]
     );
 }
@@ -133,6 +261,22 @@ fn test_keywords() {
             token!(Eof ; "" ; "" ; 1 ; 78 ; 78)
         ]
     );
+
+    assert_eq!(
+        lex("eprint"),
+        vec![
+            token!(Eprint ; "eprint" ; "" ; 1 ; 0 ; 6),
+            token!(Eof ; "" ; "" ; 1 ; 6 ; 6)
+        ]
+    );
+
+    assert_eq!(
+        lex("const"),
+        vec![
+            token!(Const ; "const" ; "" ; 1 ; 0 ; 5),
+            token!(Eof ; "" ; "" ; 1 ; 5 ; 5)
+        ]
+    );
 }
 
 #[test]
@@ -146,7 +290,7 @@ fn test_nums() {
         lex(".123;"),
         vec![
             token!(Dot ; "." ; "" ; 1 ; 0 ; 1),
-            token!(Num ; "123" ; "123" ; 1 ; 1 ; 4),
+            token!(Int ; "123" ; "123" ; 1 ; 1 ; 4),
             token!(Semicolon ; ";" ; "" ; 1 ; 4 ; 5),
             token!(Eof ; "" ; "" ; 1 ; 5; 5),
         ]
@@ -156,7 +300,7 @@ fn test_nums() {
         lex("print 123;"),
         vec![
             token!(Print ; "print" ; "" ; 1 ; 0 ; 5),
-            token!(Num ; "123" ; "123" ; 1 ; 6 ; 9),
+            token!(Int ; "123" ; "123" ; 1 ; 6 ; 9),
             token!(Semicolon ; ";" ; "" ; 1 ; 9 ; 10),
             token!(Eof ; "" ; "" ; 1 ; 10 ; 10),
         ]
@@ -166,7 +310,7 @@ fn test_nums() {
         lex("print 0;"),
         vec![
             token!(Print ; "print" ; "" ; 1 ; 0 ; 5),
-            token!(Num ; "0" ; "0" ; 1 ; 6 ; 7),
+            token!(Int ; "0" ; "0" ; 1 ; 6 ; 7),
             token!(Semicolon ; ";" ; "" ; 1 ; 7 ; 8),
             token!(Eof ; "" ; "" ; 1 ; 8 ; 8),
         ]
@@ -177,7 +321,7 @@ fn test_nums() {
         vec![
             token!(Print ; "print" ; "" ; 1 ; 0 ; 5),
             token!(Minus ; "-" ; "" ; 1 ; 6 ; 7),
-            token!(Num ; "0" ; "0" ; 1 ; 7 ; 8),
+            token!(Int ; "0" ; "0" ; 1 ; 7 ; 8),
             token!(Semicolon ; ";" ; "" ; 1 ; 8 ; 9),
             token!(Eof ; "" ; "" ; 1 ; 9 ; 9),
         ]
@@ -203,4 +347,43 @@ fn test_nums() {
             token!(Eof ; "" ; "" ; 1 ; 13 ; 13),
         ]
     );
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_number_literals() {
+    assert_eq!(
+        lex("0xFF 0b1010 0o17 1e-9 6.02E23 1_000_000"),
+        vec![
+            token!(Int ; "0xFF" ; "255" ; 1 ; 0 ; 4),
+            token!(Int ; "0b1010" ; "10" ; 1 ; 5 ; 11),
+            token!(Int ; "0o17" ; "15" ; 1 ; 12 ; 16),
+            token!(Num ; "1e-9" ; "1e-9" ; 1 ; 17 ; 21),
+            token!(Num ; "6.02E23" ; "6.02E23" ; 1 ; 22 ; 29),
+            token!(Int ; "1_000_000" ; "1000000" ; 1 ; 30 ; 39),
+            token!(Eof ; "" ; "" ; 1 ; 39 ; 39),
+        ]
+    );
+
+    assert_eq!(
+        lex("0xFFFFFFFFFFFFFFFFFF"),
+        vec![
+            token!(Int ; "0xFFFFFFFFFFFFFFFFFF" ; "4722366482869645213695" ; 1 ; 0 ; 20),
+            token!(Eof ; "" ; "" ; 1 ; 20 ; 20),
+        ]
+    );
+
+    assert_eq!(lex("0x"), vec![]);
+    assert_eq!(lex("1e"), vec![]);
+    assert_eq!(lex("1e+;"), vec![]);
+    assert_eq!(lex("0b102"), vec![]);
+    assert_eq!(lex("1__000"), vec![]);
+    assert_eq!(lex("1000_"), vec![]);
+    assert_eq!(lex("0x_FF"), vec![]);
+    assert_eq!(lex("1_.5"), vec![]);
+}
+
+#[test]
+fn test_line_numbers() {
+    let lines: Vec<usize> = lex("a\nb\r\nc\rd # e\nf").iter().map(|t| t.line).collect();
+    assert_eq!(lines, vec![1, 2, 3, 4, 5, 5]);
+}
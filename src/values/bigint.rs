@@ -0,0 +1,347 @@
+use std::{cmp::Ordering, fmt};
+
+/// The most bits the result of `BigInt::pow` can have, about 79,000 decimal digits. This stops a
+/// short expression like `2 ** 9223372036854775807` from running out of memory
+const MAX_POW_BITS: u64 = 1 << 18;
+
+/// An arbitrary precision integer, which integers are promoted to when an operation on them
+/// overflows an `i64`.
+///
+/// The magnitude is stored as base 2^32 digits with the least significant digit first, and never
+/// has trailing zero digits, so that zero is an empty vector and every value has exactly one
+/// representation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BigInt {
+    negative: bool,
+    digits: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        return Self { negative: false, digits: Vec::new() };
+    }
+
+    pub fn from_i64(n: i64) -> Self {
+        let magnitude = Self::from_u64(n.unsigned_abs());
+        return Self::from_magnitude(n < 0, magnitude.digits);
+    }
+
+    pub fn from_u64(mut n: u64) -> Self {
+        let mut digits = Vec::new();
+        while n > 0 {
+            digits.push(n as u32);
+            n >>= 32;
+        }
+        return Self { negative: false, digits };
+    }
+
+    /// Parses a decimal integer, returning `None` if it contains anything other than digits
+    /// after an optional minus sign
+    pub fn parse(text: &str) -> Option<Self> {
+        return Self::parse_radix(text, 10);
+    }
+
+    /// Parses an integer written in the given base, such as the digits of a hex literal
+    pub fn parse_radix(text: &str, radix: u32) -> Option<Self> {
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text),
+        };
+        if digits.is_empty() {
+            return None;
+        }
+
+        let mut result = Self::zero();
+        for c in digits.chars() {
+            let digit = c.to_digit(radix)?;
+            result = Self::from_magnitude(false, mul_small(&result.digits, radix, digit));
+        }
+
+        result.negative = negative && !result.is_zero();
+        return Some(result);
+    }
+
+    pub fn is_zero(&self) -> bool {
+        return self.digits.is_empty();
+    }
+
+    /// Converts to an `i64` if the value fits in one
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.digits.len() > 2 {
+            return None;
+        }
+
+        let mut magnitude: u64 = 0;
+        for (i, digit) in self.digits.iter().enumerate() {
+            magnitude |= (*digit as u64) << (32 * i);
+        }
+
+        if self.negative {
+            if magnitude == i64::MIN.unsigned_abs() {
+                return Some(i64::MIN);
+            }
+            return i64::try_from(magnitude).ok().map(|n| -n);
+        }
+        return i64::try_from(magnitude).ok();
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self.digits
+            .iter()
+            .rev()
+            .fold(0.0, |total, digit| total * 4294967296.0 + *digit as f64);
+
+        if self.negative {
+            return -magnitude;
+        }
+        return magnitude;
+    }
+
+    pub fn neg(&self) -> Self {
+        return Self::from_magnitude(!self.negative, self.digits.clone());
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        if self.negative == other.negative {
+            return Self::from_magnitude(self.negative, add_magnitudes(&self.digits, &other.digits));
+        }
+
+        // The signs differ, so the smaller magnitude is taken away from the larger one, and the
+        // result has the sign of the larger one
+        return match cmp_magnitudes(&self.digits, &other.digits) {
+            Ordering::Less => Self::from_magnitude(
+                other.negative,
+                sub_magnitudes(&other.digits, &self.digits),
+            ),
+            _ => Self::from_magnitude(self.negative, sub_magnitudes(&self.digits, &other.digits)),
+        };
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        return self.add(&other.neg());
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        let mut result = vec![0u32; self.digits.len() + other.digits.len()];
+
+        for (i, a) in self.digits.iter().enumerate() {
+            let mut carry: u64 = 0;
+            for (j, b) in other.digits.iter().enumerate() {
+                let total = result[i + j] as u64 + (*a as u64) * (*b as u64) + carry;
+                result[i + j] = total as u32;
+                carry = total >> 32;
+            }
+            result[i + other.digits.len()] = carry as u32;
+        }
+
+        return Self::from_magnitude(self.negative != other.negative, result);
+    }
+
+    /// Divides by `other`, rounding the quotient towards zero so that the remainder has the same
+    /// sign as `self`, like the `/` and `%` operators on Rust integers. Returns `None` if `other`
+    /// is zero
+    pub fn div_rem(&self, other: &Self) -> Option<(Self, Self)> {
+        if other.is_zero() {
+            return None;
+        }
+
+        let (quotient, remainder) = div_rem_magnitudes(&self.digits, &other.digits);
+        return Some((
+            Self::from_magnitude(self.negative != other.negative, quotient),
+            Self::from_magnitude(self.negative, remainder),
+        ));
+    }
+
+    /// Divides by `other`, rounding the quotient down. Returns `None` if `other` is zero
+    pub fn div_floor(&self, other: &Self) -> Option<Self> {
+        let (quotient, remainder) = self.div_rem(other)?;
+
+        if !remainder.is_zero() && self.negative != other.negative {
+            return Some(quotient.sub(&Self::from_i64(1)));
+        }
+        return Some(quotient);
+    }
+
+    /// The number of bits in the magnitude, which is 0 for zero
+    pub fn bits(&self) -> u64 {
+        return match self.digits.last() {
+            Some(last) => self.digits.len() as u64 * 32 - last.leading_zeros() as u64,
+            None => 0,
+        };
+    }
+
+    /// Raises to the power of `exponent`, or returns `None` if the result would be too large to
+    /// work out. Powers of 0, 1 and -1 never grow, so they can have any exponent
+    pub fn pow(&self, mut exponent: u64) -> Option<Self> {
+        // A base of `bits` bits gives a result of more than `(bits - 1) * exponent` bits
+        if self.bits().saturating_sub(1).saturating_mul(exponent) > MAX_POW_BITS {
+            return None;
+        }
+
+        let mut result = Self::from_i64(1);
+        let mut base = self.clone();
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.mul(&base);
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.mul(&base);
+            }
+        }
+
+        return Some(result);
+    }
+
+    fn from_magnitude(negative: bool, mut digits: Vec<u32>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        let negative = negative && !digits.is_empty();
+        return Self { negative, digits };
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitudes(&self.digits, &other.digits),
+            (true, true) => cmp_magnitudes(&other.digits, &self.digits),
+        };
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // Repeatedly divides by 10^9 to get the decimal digits nine at a time
+        let mut chunks = Vec::new();
+        let mut magnitude = self.digits.clone();
+        while !magnitude.is_empty() {
+            let (quotient, remainder) = div_small(&magnitude, 1_000_000_000);
+            chunks.push(remainder);
+            magnitude = quotient;
+            while magnitude.last() == Some(&0) {
+                magnitude.pop();
+            }
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", chunks[chunks.len() - 1])?;
+        for chunk in chunks.iter().rev().skip(1) {
+            write!(f, "{chunk:09}")?;
+        }
+
+        return Ok(());
+    }
+}
+
+fn cmp_magnitudes(a: &[u32], b: &[u32]) -> Ordering {
+    if a.len() != b.len() {
+        return a.len().cmp(&b.len());
+    }
+    return a.iter().rev().cmp(b.iter().rev());
+}
+
+fn add_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry: u64 = 0;
+
+    for i in 0..a.len().max(b.len()) {
+        let total = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        result.push(total as u32);
+        carry = total >> 32;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+
+    return result;
+}
+
+/// Takes `b` away from `a`, where the magnitude of `a` must be at least that of `b`
+fn sub_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow: i64 = 0;
+
+    for (i, digit) in a.iter().enumerate() {
+        let mut total = *digit as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if total < 0 {
+            total += 1 << 32;
+            borrow = 1;
+        }
+        result.push(total as u32);
+    }
+
+    return result;
+}
+
+/// Multiplies a magnitude by a small number, then adds another small number to it
+fn mul_small(a: &[u32], factor: u32, addend: u32) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len() + 1);
+    let mut carry = addend as u64;
+
+    for digit in a {
+        let total = *digit as u64 * factor as u64 + carry;
+        result.push(total as u32);
+        carry = total >> 32;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+
+    return result;
+}
+
+fn div_small(a: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder: u64 = 0;
+
+    for i in (0..a.len()).rev() {
+        let current = (remainder << 32) | a[i] as u64;
+        quotient[i] = (current / divisor as u64) as u32;
+        remainder = current % divisor as u64;
+    }
+
+    return (quotient, remainder as u32);
+}
+
+/// Long division, one bit at a time
+fn div_rem_magnitudes(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+
+    for i in (0..a.len() * 32).rev() {
+        // Shifts the remainder left by one bit and brings down the next bit of `a`
+        let bit = (a[i / 32] >> (i % 32)) & 1;
+        remainder = mul_small(&remainder, 2, bit);
+        while remainder.last() == Some(&0) {
+            remainder.pop();
+        }
+
+        if cmp_magnitudes(&remainder, b) != Ordering::Less {
+            remainder = sub_magnitudes(&remainder, b);
+            while remainder.last() == Some(&0) {
+                remainder.pop();
+            }
+            quotient[i / 32] |= 1 << (i % 32);
+        }
+    }
+
+    return (quotient, remainder);
+}
@@ -5,13 +5,20 @@ use std::{
 };
 
 use crate::{
+    convert::{arity_of, check_order, NativeHandler, ParamKind},
     enviromnent::Environment,
     error::InterpreterError,
+    generator::{contains_yield, Generator},
     interpreter::{Env, Interpreter},
-    stmt::Stmt,
+    list::List,
+    stmt::{Parameter, Stmt},
+    token::{Token, TokenType},
     value::{LiteralType, Value},
 };
 
+/// Arguments passed by name at a call site, like `b: 3` in `f(1, b: 3)`
+pub type KeywordArgs = Vec<(String, Value)>;
+
 pub trait Callable {
     fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, InterpreterError>;
 }
@@ -19,14 +26,15 @@ pub trait Callable {
 #[derive(Clone, Debug)]
 pub struct Func {
     name: String,
-    pub arity: usize,
+    pub arity: Arity,
     declaration: Stmt,
     closure: Env,
+    is_generator: bool, // Whether the body contains a yield, so calling it returns a generator
+    module: Option<String>, // The module it was declared in, so that its errors can say where they happened
 }
 
 impl PartialEq for Func {
     fn eq(&self, other: &Self) -> bool {
-        println!("Should never be called");
         return self.name == other.name
             && self.arity == other.arity
             && self.declaration == other.declaration;
@@ -40,55 +48,381 @@ impl PartialOrd for Func {
 }
 
 impl Func {
-    pub fn new(declaration: Stmt, closure: Env) -> Result<Self, InterpreterError> {
+    pub fn new(declaration: Stmt, closure: Env, module: Option<String>) -> Result<Self, InterpreterError> {
         match &declaration {
-            Stmt::Function { name, params, .. } => {
+            Stmt::Function { name, params, body } => {
                 return Ok(Self {
                     name: name.lexeme.clone(),
-                    arity: params.len(),
+                    arity: arity_of_params(params),
+                    is_generator: body.iter().any(contains_yield),
                     declaration,
                     closure,
+                    module,
                 });
             },
             _ => return Err(InterpreterError::ExpectedFunctionStatementForDeclaration),
         }
     }
-}
 
-impl Callable for Func {
-    fn call(&self, interpreter: &mut crate::interpreter::Interpreter, arguments: Vec<Value>) -> Result<Value, InterpreterError> {
+    /// Creates a function from an anonymous function expression, giving it the name `lambda`
+    pub fn lambda(
+        keyword: &Token,
+        params: Vec<Parameter>,
+        body: Vec<Stmt>,
+        closure: Env,
+        module: Option<String>,
+    ) -> Self {
+        let name = Token::new(
+            TokenType::Identifier,
+            "lambda".to_string(),
+            String::new(),
+            keyword.line,
+            keyword.start,
+            keyword.end,
+        );
+
+        return Self {
+            name: name.lexeme.clone(),
+            arity: arity_of_params(&params),
+            is_generator: body.iter().any(contains_yield),
+            declaration: Stmt::Function { name, params, body },
+            closure,
+            module,
+        };
+    }
+
+    /// Calls the function with positional and keyword arguments. Parameters without an argument
+    /// get their default value, which is evaluated in the function's environment so that it can
+    /// refer to earlier parameters
+    pub fn call_with(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+        keywords: KeywordArgs,
+    ) -> Result<Value, InterpreterError> {
         match &self.declaration {
             Stmt::Function { name: _, params, body } => {
                 let environment = Rc::new(RefCell::new(Environment::new(Some(Rc::clone(
                     &self.closure,
                 )))));
 
-                for i in 0..params.len() {
-                    environment
-                        .borrow_mut()
-                        .define(params[i].lexeme.clone(), arguments[i].clone());
+                let count = arguments.len() + keywords.len();
+                let keywords_given = !keywords.is_empty();
+                let names: Vec<String> = params
+                    .iter()
+                    .filter(|p| !p.variadic)
+                    .map(|p| p.name.lexeme.clone())
+                    .collect();
+                let (named, rest) = bind_arguments(&self.name, &names, arguments, keywords)?;
+
+                let is_variadic = matches!(self.arity, Arity::Variadic(_));
+                let missing = named.iter().zip(params).find(|(v, p)| v.is_none() && p.default.is_none());
+
+                // With keyword arguments, the count alone does not show which one was left out
+                if let Some((_, param)) = missing.filter(|_| keywords_given) {
+                    return Err(InterpreterError::MissingArgument {
+                        function: self.name.clone(),
+                        param: param.name.lexeme.clone(),
+                    });
+                }
+                if missing.is_some() || (!rest.is_empty() && !is_variadic) {
+                    return Err(InterpreterError::ArgsOutsideArity {
+                        name: self.name.clone(),
+                        args: count,
+                        arity: self.arity,
+                    });
+                }
+
+                let mut rest = Some(rest);
+                for (i, param) in params.iter().enumerate() {
+                    let value = match named.get(i).cloned().flatten() {
+                        Some(v) => v,
+                        None if param.variadic => {
+                            Value::List(List::new(rest.take().unwrap_or_default()))
+                        }
+                        None => match &param.default {
+                            Some(default) => interpreter
+                                .evaluate_in(default, Rc::clone(&environment))
+                                .map_err(|e| self.error_in_module(interpreter, e))?,
+                            None => Value::Literal(LiteralType::Null),
+                        },
+                    };
+                    environment.borrow_mut().define(param.name.lexeme.clone(), value);
+                }
+
+                if self.is_generator {
+                    let generator = Generator::new(self.name.clone(), body.clone(), environment);
+                    return Ok(Value::Generator(generator));
                 }
 
                 return match interpreter.execute_block(body.clone(), environment) {
                     Ok(_) => Ok(Value::Literal(LiteralType::Null)),
-                    Err(r) => Ok(r?)
+                    Err(r) => r.map_err(|e| self.error_in_module(interpreter, e)),
                 }
             }
             _ => return Err(InterpreterError::ExpectedDeclarationToBeAFunction),
         }
     }
+
+    /// Gives an error from the body of a function declared in a module the module's name and the
+    /// line it happened on, as the importer running it would otherwise report it as its own
+    fn error_in_module(&self, interpreter: &Interpreter, error: InterpreterError) -> InterpreterError {
+        return match (&self.module, error) {
+            (_, e @ InterpreterError::ErrorInModule { .. }) | (None, e) => e,
+            (Some(path), e) => InterpreterError::ErrorInModule {
+                path: path.clone(),
+                line: interpreter.line(),
+                message: e.to_string(),
+            },
+        };
+    }
+}
+
+impl Callable for Func {
+    fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, InterpreterError> {
+        return self.call_with(interpreter, arguments, Vec::new());
+    }
+}
+
+/// Works out the arity of a declared function from its parameters, in the same way as for a
+/// native function
+fn arity_of_params(params: &[Parameter]) -> Arity {
+    let kinds: Vec<ParamKind> = params
+        .iter()
+        .map(|p| match p {
+            Parameter { variadic: true, .. } => ParamKind::Rest,
+            Parameter { default: Some(_), .. } => ParamKind::Optional,
+            _ => ParamKind::Required,
+        })
+        .collect();
+    return arity_of(&kinds);
+}
+
+/// Places the positional and keyword arguments of a call into a slot for each of the named
+/// parameters, leaving a slot empty when it has no argument. Positional arguments beyond the named
+/// parameters are returned separately, for a variadic parameter to collect
+fn bind_arguments(
+    function: &str,
+    params: &[String],
+    arguments: Vec<Value>,
+    keywords: KeywordArgs,
+) -> Result<(Vec<Option<Value>>, Vec<Value>), InterpreterError> {
+    let mut named: Vec<Option<Value>> = vec![None; params.len()];
+    let mut rest = Vec::new();
+
+    for (i, argument) in arguments.into_iter().enumerate() {
+        match named.get_mut(i) {
+            Some(slot) => *slot = Some(argument),
+            None => rest.push(argument),
+        }
+    }
+
+    for (name, value) in keywords {
+        let index = match params.iter().position(|p| *p == name) {
+            Some(i) => i,
+            None => return Err(InterpreterError::UnknownKeywordArgument {
+                function: function.to_string(),
+                name,
+            }),
+        };
+
+        if named[index].is_some() {
+            return Err(InterpreterError::DuplicateArgument {
+                function: function.to_string(),
+                name,
+            });
+        }
+        named[index] = Some(value);
+    }
+
+    return Ok((named, rest));
+}
+
+/// The signature shared by every native function, whether it is a builtin or a closure
+/// registered by the host through `Interpreter::register_fn`
+pub type NativeFn = Rc<dyn Fn(&mut Interpreter, Vec<Value>) -> Result<Value, InterpreterError>>;
+
+/// A native function that is given its keyword arguments as they are, rather than having them
+/// bound to named parameters
+pub type NativeKeywordFn =
+    Rc<dyn Fn(&mut Interpreter, Vec<Value>, KeywordArgs) -> Result<Value, InterpreterError>>;
+
+/// The number of arguments a callable accepts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Arity {
+    Fixed(usize),
+    Range(usize, usize), // Minimum and maximum, both INCLUSIVE
+    Variadic(usize), // The minimum, with no upper limit
+}
+
+impl Arity {
+    /// The fewest arguments that are accepted
+    pub fn min(&self) -> usize {
+        return match self {
+            Arity::Fixed(n) | Arity::Range(n, _) | Arity::Variadic(n) => *n,
+        };
+    }
+
+    /// A short form used when showing a function, like `2`, `1..=3` or `1..` for at least one
+    pub fn short(&self) -> String {
+        return match self {
+            Arity::Fixed(n) => n.to_string(),
+            Arity::Range(min, max) => format!("{min}..={max}"),
+            Arity::Variadic(min) => format!("{min}.."),
+        };
+    }
+
+    pub fn accepts(&self, args: usize) -> bool {
+        return match self {
+            Arity::Fixed(n) => args == *n,
+            Arity::Range(min, max) => args >= *min && args <= *max,
+            Arity::Variadic(min) => args >= *min,
+        };
+    }
 }
 
-#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[derive(Clone)]
 pub struct NativeFunc {
     name: String,
-    pub arity: usize,
-    fun: fn(&mut Interpreter, Vec<Value>) -> Result<Value, InterpreterError>,
+    pub arity: Arity,
+    params: Vec<String>, // The names of the parameters that can be passed as keyword arguments
+    fun: NativeFn,
+    keyword_fun: Option<NativeKeywordFn>, // Takes any keyword arguments, instead of `params` naming them
+}
+
+impl PartialEq for NativeFunc {
+    fn eq(&self, other: &Self) -> bool {
+        return self.name == other.name && Rc::ptr_eq(&self.fun, &other.fun);
+    }
+}
+
+impl PartialOrd for NativeFunc {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.name.partial_cmp(&other.name)
+    }
+}
+
+impl fmt::Debug for NativeFunc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.debug_struct("NativeFunc")
+            .field("name", &self.name)
+            .field("arity", &self.arity)
+            .finish();
+    }
 }
 
 impl NativeFunc {
-    pub fn new(name: String, arity: usize, fun: fn(&mut Interpreter, Vec<Value>) -> Result<Value, InterpreterError>) -> Self {
-        return Self { name, arity, fun };
+    pub fn new<F>(name: String, arity: Arity, fun: F) -> Self
+    where
+        F: Fn(&mut Interpreter, Vec<Value>) -> Result<Value, InterpreterError> + 'static,
+    {
+        return Self { name, arity, params: Vec::new(), fun: Rc::new(fun), keyword_fun: None };
+    }
+
+    /// Creates a function that accepts keyword arguments of any name, which are passed to `fun`
+    /// after the positional arguments. Only the positional arguments are checked against `arity`
+    pub fn with_keywords<F>(name: String, arity: Arity, fun: F) -> Self
+    where
+        F: Fn(&mut Interpreter, Vec<Value>, KeywordArgs) -> Result<Value, InterpreterError> + 'static,
+    {
+        let keyword_fun = Rc::new(fun);
+        let positional = Rc::clone(&keyword_fun);
+        return Self {
+            name,
+            arity,
+            params: Vec::new(),
+            fun: Rc::new(move |interpreter, args| positional(interpreter, args, Vec::new())),
+            keyword_fun: Some(keyword_fun),
+        };
+    }
+
+    /// Wraps a Rust closure whose parameters and return value are converted to and from script
+    /// values, naming each parameter with `params`
+    pub fn from_handler<Args, F>(name: &str, params: &[&str], fun: F) -> Self
+    where
+        F: NativeHandler<Args> + 'static,
+    {
+        let kinds = F::kinds();
+        check_order(name, &kinds);
+        let arity = arity_of(&kinds);
+        let function = name.to_string();
+        let params: Vec<String> = params.iter().map(|p| p.to_string()).collect();
+
+        // A trailing `Rest<T>` parameter collects the remaining positional arguments, so it
+        // cannot be passed by name
+        let named: Vec<String> = params
+            .iter()
+            .zip(kinds.iter())
+            .filter(|(_, kind)| **kind != ParamKind::Rest)
+            .map(|(p, _)| p.clone())
+            .collect();
+
+        return NativeFunc::new(name.to_string(), arity, move |_, args| {
+            fun.invoke(&function, &params, args)
+        })
+        .with_params(named);
+    }
+
+    /// Names the parameters, in order, so that they can be passed as keyword arguments
+    pub fn with_params(mut self, params: Vec<String>) -> Self {
+        self.params = params;
+        return self;
+    }
+
+    /// Calls the function with positional and keyword arguments, after checking them against its
+    /// arity. An optional parameter that is skipped over by a keyword argument is passed `null`
+    pub fn call_with(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<Value>,
+        keywords: KeywordArgs,
+    ) -> Result<Value, InterpreterError> {
+        if let Some(keyword_fun) = &self.keyword_fun {
+            if !self.arity.accepts(arguments.len()) {
+                return Err(InterpreterError::ArgsOutsideArity {
+                    name: self.name.clone(),
+                    args: arguments.len(),
+                    arity: self.arity,
+                });
+            }
+            return keyword_fun(interpreter, arguments, keywords);
+        }
+
+        let count = arguments.len() + keywords.len();
+        let outside_arity = InterpreterError::ArgsOutsideArity {
+            name: self.name.clone(),
+            args: count,
+            arity: self.arity,
+        };
+
+        if keywords.is_empty() {
+            if !self.arity.accepts(count) {
+                return Err(outside_arity);
+            }
+            return self.call(interpreter, arguments);
+        }
+
+        let (mut named, rest) = bind_arguments(&self.name, &self.params, arguments, keywords)?;
+        if let Some(i) = named.iter().take(self.arity.min()).position(|v| v.is_none()) {
+            return Err(InterpreterError::MissingArgument {
+                function: self.name.clone(),
+                param: self.params[i].clone(),
+            });
+        }
+        while named.last().is_some_and(|v| v.is_none()) {
+            named.pop();
+        }
+
+        let mut arguments: Vec<Value> = named
+            .into_iter()
+            .map(|v| v.unwrap_or(Value::Literal(LiteralType::Null)))
+            .collect();
+        arguments.extend(rest);
+
+        if !self.arity.accepts(arguments.len()) {
+            return Err(outside_arity);
+        }
+        return self.call(interpreter, arguments);
     }
 }
 
@@ -100,12 +434,28 @@ impl Callable for NativeFunc {
 
 impl fmt::Display for NativeFunc {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        return write!(f, "{}({}) {{{:?}}}", self.name, self.arity, self.fun);
+        return write!(f, "<native {}/{}>", self.name, self.arity.short());
     }
 }
 
 impl fmt::Display for Func {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        return write!(f, "{}({}) {{{}}}", self.name, self.arity, self.declaration);
+        return write!(f, "<fn {}/{}>", self.name, self.arity.short());
+    }
+}
+
+/// Shown with the word "arguments" for errors, like `1 argument` or `1 to 2 arguments`
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Arity::Fixed(n) => write!(f, "{n} {}", arguments(*n)),
+            Arity::Range(min, max) => write!(f, "{min} to {max} arguments"),
+            Arity::Variadic(min) => write!(f, "at least {min} {}", arguments(*min)),
+        };
     }
-}
\ No newline at end of file
+}
+
+/// The word "argument", made plural unless there is exactly one
+pub fn arguments(count: usize) -> &'static str {
+    return if count == 1 { "argument" } else { "arguments" };
+}
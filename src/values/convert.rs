@@ -0,0 +1,407 @@
+use std::collections::VecDeque;
+
+use crate::{
+    bigint::BigInt,
+    callable::Arity,
+    error::InterpreterError,
+    list::List,
+    native_handler,
+    number::Number,
+    value::{LiteralType, Value},
+};
+
+/// Converts a script value into a Rust value, handing the value back if it is the wrong type
+pub trait FromValue: Sized {
+    fn from_value(value: Value) -> Result<Self, Value>;
+
+    /// The type that was expected, used for the error when a conversion fails
+    fn expected() -> String;
+
+    /// Whether a missing argument can be converted from `null`
+    fn is_optional() -> bool {
+        return false;
+    }
+}
+
+/// Converts a Rust value into a script value
+pub trait IntoValue {
+    fn into_value(self) -> Value;
+}
+
+/// Collects every remaining argument of a native function, e.g. `|nums: Rest<f64>| ...`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rest<T>(pub Vec<T>);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParamKind {
+    Required,
+    Optional,
+    Rest,
+}
+
+/// A single parameter of a closure registered with `Interpreter::register_fn`
+pub trait NativeParam: Sized {
+    fn kind() -> ParamKind;
+
+    fn take(args: &mut VecDeque<Value>, function: &str, param: &str) -> Result<Self, InterpreterError>;
+}
+
+/// The return type of a closure registered with `Interpreter::register_fn`, which can either be a
+/// plain value or a `Result` for closures that can fail
+pub trait NativeReturn {
+    fn into_result(self) -> Result<Value, InterpreterError>;
+}
+
+/// Implemented for every closure whose parameters and return type can be converted, so that it
+/// can be wrapped in a `NativeFunc`
+pub trait NativeHandler<Args> {
+    fn kinds() -> Vec<ParamKind>;
+
+    fn invoke(&self, function: &str, params: &[String], args: Vec<Value>) -> Result<Value, InterpreterError>;
+}
+
+/// Checks that required parameters come first, then optional ones, and that a `Rest<T>` parameter
+/// is only ever last. A closure with any other order could never be called as expected, so it is
+/// rejected when it is registered rather than when it is called
+pub fn check_order(function: &str, kinds: &[ParamKind]) {
+    let rank = |kind: ParamKind| match kind {
+        ParamKind::Required => 0,
+        ParamKind::Optional => 1,
+        ParamKind::Rest => 2,
+    };
+    let describe = |kind: ParamKind| match kind {
+        ParamKind::Required => "a required",
+        ParamKind::Optional => "an optional",
+        ParamKind::Rest => "a rest",
+    };
+
+    for pair in kinds.windows(2) {
+        if rank(pair[0]) > rank(pair[1]) || pair[0] == ParamKind::Rest {
+            panic!(
+                "Native function '{function}' has {} parameter before {} one. Required parameters \
+                 must come first, then optional ones, then at most one rest parameter",
+                describe(pair[0]),
+                describe(pair[1])
+            );
+        }
+    }
+}
+
+/// Works out the arity of a native function from the kinds of its parameters
+pub fn arity_of(kinds: &[ParamKind]) -> Arity {
+    let required = kinds.iter().filter(|k| **k == ParamKind::Required).count();
+    let optional = kinds.iter().filter(|k| **k == ParamKind::Optional).count();
+
+    if kinds.contains(&ParamKind::Rest) {
+        return Arity::Variadic(required);
+    }
+    if optional > 0 {
+        return Arity::Range(required, required + optional);
+    }
+    return Arity::Fixed(required);
+}
+
+fn mismatch(function: &str, param: &str, expected: String, got: &Value) -> InterpreterError {
+    return InterpreterError::MismatchedArgumentType {
+        function: function.to_string(),
+        param: param.to_string(),
+        expected,
+        got: got.type_name().to_string(),
+    };
+}
+
+impl FromValue for Value {
+    fn from_value(value: Value) -> Result<Self, Value> {
+        return Ok(value);
+    }
+
+    fn expected() -> String {
+        return "any value".to_string();
+    }
+}
+
+/// Accepts ints as well as floats, converting them to a float
+impl FromValue for f64 {
+    fn from_value(value: Value) -> Result<Self, Value> {
+        return match Number::from_value(&value) {
+            Some(n) => Ok(n.to_f64()),
+            None => Err(value),
+        };
+    }
+
+    fn expected() -> String {
+        return "num".to_string();
+    }
+}
+
+/// Keeps ints as ints, for functions whose result has the same type as their argument
+impl FromValue for Number {
+    fn from_value(value: Value) -> Result<Self, Value> {
+        return match Number::from_value(&value) {
+            Some(n) => Ok(n),
+            None => Err(value),
+        };
+    }
+
+    fn expected() -> String {
+        return "num".to_string();
+    }
+}
+
+impl FromValue for i64 {
+    fn from_value(value: Value) -> Result<Self, Value> {
+        return match value {
+            Value::Literal(LiteralType::Int(n)) => Ok(n),
+            _ => Err(value),
+        };
+    }
+
+    fn expected() -> String {
+        return "whole num".to_string();
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: Value) -> Result<Self, Value> {
+        return match value {
+            Value::Literal(LiteralType::Str(s)) => Ok(s),
+            _ => Err(value),
+        };
+    }
+
+    fn expected() -> String {
+        return "str".to_string();
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: Value) -> Result<Self, Value> {
+        return match value {
+            Value::Literal(LiteralType::True) => Ok(true),
+            Value::Literal(LiteralType::False) => Ok(false),
+            _ => Err(value),
+        };
+    }
+
+    fn expected() -> String {
+        return "bool".to_string();
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: Value) -> Result<Self, Value> {
+        return match value {
+            Value::List(list) => {
+                let mut items = Vec::new();
+                for item in list.values.iter() {
+                    match T::from_value(item.clone()) {
+                        Ok(v) => items.push(v),
+                        Err(_) => return Err(Value::List(list)),
+                    }
+                }
+                Ok(items)
+            },
+            _ => Err(value),
+        };
+    }
+
+    fn expected() -> String {
+        return format!("list of {}", T::expected());
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: Value) -> Result<Self, Value> {
+        return match value {
+            Value::Literal(LiteralType::Null) => Ok(None),
+            _ => Ok(Some(T::from_value(value)?)),
+        };
+    }
+
+    fn expected() -> String {
+        return format!("{} or null", T::expected());
+    }
+
+    fn is_optional() -> bool {
+        return true;
+    }
+}
+
+impl IntoValue for Value {
+    fn into_value(self) -> Value {
+        return self;
+    }
+}
+
+impl IntoValue for () {
+    fn into_value(self) -> Value {
+        return Value::Literal(LiteralType::Null);
+    }
+}
+
+impl IntoValue for f64 {
+    fn into_value(self) -> Value {
+        return Value::Literal(LiteralType::Num(self));
+    }
+}
+
+impl IntoValue for Number {
+    fn into_value(self) -> Value {
+        return Number::into_value(self);
+    }
+}
+
+impl IntoValue for String {
+    fn into_value(self) -> Value {
+        return Value::Literal(LiteralType::Str(self));
+    }
+}
+
+impl IntoValue for &str {
+    fn into_value(self) -> Value {
+        return Value::Literal(LiteralType::Str(self.to_string()));
+    }
+}
+
+impl IntoValue for bool {
+    fn into_value(self) -> Value {
+        return Value::Literal(if self { LiteralType::True } else { LiteralType::False });
+    }
+}
+
+impl IntoValue for i64 {
+    fn into_value(self) -> Value {
+        return Value::Literal(LiteralType::Int(self));
+    }
+}
+
+impl IntoValue for i32 {
+    fn into_value(self) -> Value {
+        return Value::Literal(LiteralType::Int(self as i64));
+    }
+}
+
+impl IntoValue for usize {
+    fn into_value(self) -> Value {
+        return Number::Big(BigInt::from_u64(self as u64)).into_value();
+    }
+}
+
+impl<T: IntoValue> IntoValue for Vec<T> {
+    fn into_value(self) -> Value {
+        return Value::List(List::new(self.into_iter().map(|v| v.into_value()).collect()));
+    }
+}
+
+impl<T: IntoValue> IntoValue for Option<T> {
+    fn into_value(self) -> Value {
+        return match self {
+            Some(v) => v.into_value(),
+            None => Value::Literal(LiteralType::Null),
+        };
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        return value.into_value();
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        return value.into_value();
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        return value.into_value();
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Self {
+        return value.into_value();
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        return value.into_value();
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        return value.into_value();
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        return value.into_value();
+    }
+}
+
+impl<T: IntoValue> From<Vec<T>> for Value {
+    fn from(value: Vec<T>) -> Self {
+        return value.into_value();
+    }
+}
+
+impl<T: IntoValue> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        return value.into_value();
+    }
+}
+
+impl<T: FromValue> NativeParam for T {
+    fn kind() -> ParamKind {
+        if T::is_optional() {
+            return ParamKind::Optional;
+        }
+        return ParamKind::Required;
+    }
+
+    fn take(args: &mut VecDeque<Value>, function: &str, param: &str) -> Result<Self, InterpreterError> {
+        let arg = args.pop_front().unwrap_or(Value::Literal(LiteralType::Null));
+        return T::from_value(arg).map_err(|v| mismatch(function, param, T::expected(), &v));
+    }
+}
+
+impl<T: FromValue> NativeParam for Rest<T> {
+    fn kind() -> ParamKind {
+        return ParamKind::Rest;
+    }
+
+    fn take(args: &mut VecDeque<Value>, function: &str, param: &str) -> Result<Self, InterpreterError> {
+        let mut items = Vec::new();
+        for (i, arg) in args.drain(..).enumerate() {
+            let item = T::from_value(arg)
+                .map_err(|v| mismatch(function, &format!("{param}[{i}]"), T::expected(), &v))?;
+            items.push(item);
+        }
+        return Ok(Rest(items));
+    }
+}
+
+impl<T: IntoValue> NativeReturn for T {
+    fn into_result(self) -> Result<Value, InterpreterError> {
+        return Ok(self.into_value());
+    }
+}
+
+impl<T: IntoValue> NativeReturn for Result<T, InterpreterError> {
+    fn into_result(self) -> Result<Value, InterpreterError> {
+        return self.map(|v| v.into_value());
+    }
+}
+
+native_handler!();
+native_handler!(A);
+native_handler!(A, B);
+native_handler!(A, B, C);
+native_handler!(A, B, C, D);
+native_handler!(A, B, C, D, E);
+native_handler!(A, B, C, D, E, F);
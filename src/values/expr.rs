@@ -3,6 +3,8 @@ use std::fmt;
 
 use crate::{
     expr_visitor,
+    pattern::Pattern,
+    stmt::{Parameter, Stmt},
     token::{Token, TokenType},
     value::LiteralType,
 };
@@ -10,8 +12,9 @@ use crate::{
 #[derive(Clone, Debug, PartialEq)]
 pub enum Expr {
     Alteration {
-        name: Token, // Variable name
+        target: Box<Expr>, // A variable or a list element
         alteration_type: TokenType, // Incr or Decr tokens
+        prefix: bool, // Prefix alterations return the new value, postfix ones the old value
     },
     Assign {
         name: Token, // Variable name
@@ -25,30 +28,68 @@ pub enum Expr {
     Call {
         callee: Box<Expr>, // The name of the call, e.g. the function name
         arguments: Vec<Expr>, // The arguments passed in the parenthesise
+        keywords: Vec<(Token, Expr)>, // Arguments passed by name after the positional ones, e.g. 'b: 3'
+    },
+    Conditional {
+        condition: Box<Expr>,
+        then_branch: Box<Expr>, // The value if the condition is truthy
+        else_branch: Box<Expr>, // The value if the condition is falsy
+    },
+    CompoundAssign {
+        target: Box<Expr>, // A variable or a list element
+        operator: Token, // The operator, such as '+=' or '*='
+        value: Box<Expr>,
+    },
+    DestructureAssign {
+        pattern: Pattern, // A list of existing variables, written like `[a, b] = [b, a]`
+        value: Box<Expr>,
     },
     Grouping {
         expression: Box<Expr>, // The expresion in brackets, usually binary
     },
+    IndexAssign {
+        list: Token, // The name of the variable for the list
+        index: Box<Expr>,
+        value: Box<Expr>,
+    },
+    Lambda {
+        keyword: Token, // The 'def' or '=>' token, used to name and locate the function
+        params: Vec<Parameter>,
+        body: Vec<Stmt>, // An arrow function's expression is wrapped in a return statement
+    },
     List {
         items: Vec<Expr>, // The items to be in the created list
     },
     ListMethodCall {
         object: Token, // The name of the instance that the method is being called on
         call: Box<Expr>, // A call expression for the method call
+        optional: bool, // Called with '?.', which gives null without calling if the object is null
     },
     Literal {
         value: LiteralType,
     },
+    MethodCall {
+        object: Box<Expr>, // Any other expression, such as the result of a call in `g.map(f).take(2)`
+        call: Box<Expr>, // A call expression for the method call
+        optional: bool, // Called with '?.', which gives null without calling if the object is null
+    },
     Logical {
         left: Box<Expr>,
         operator: Token,
         right: Box<Expr>,
     },
+    Range {
+        start: Box<Expr>,
+        operator: Token, // Either '..' or '..=' for an inclusive range
+        end: Box<Expr>,
+        step: Option<Box<Expr>>,
+    },
     Splice {
         list: Token, // The name of the variable for the list
         is_splice: bool, // Check if it is a splice to see if returning list or value
         start: Option<Box<Expr>>, // The start index INCLUSIVE
         end: Option<Box<Expr>>, // The end index INCLUSIVE
+        optional: bool, // Indexed with '?[', which gives null if the list is null
     },
     Unary {
         operator: Token,
@@ -62,23 +103,53 @@ pub enum Expr {
 impl fmt::Display for Expr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         return match self {
-            Expr::Alteration { name, alteration_type } => {
-                write!(f, "Alteration({name} {alteration_type})")
+            Expr::Alteration { target, alteration_type, prefix } => {
+                if *prefix {
+                    write!(f, "Alteration({alteration_type} {target})")
+                } else {
+                    write!(f, "Alteration({target} {alteration_type})")
+                }
             },
             Expr::Assign { name, value } => write!(f, "Assign({name} = {value}"),
             Expr::Binary { left, operator, right } => {
                 write!(f, "Binary({left} {operator} {right})")
             },
-            Expr::Call { callee, arguments } => write!(f, "Call({callee} {arguments:?})"),
+            Expr::Call { callee, arguments, keywords } => {
+                write!(f, "Call({callee} {arguments:?} {keywords:?})")
+            },
+            Expr::Conditional { condition, then_branch, else_branch } => {
+                write!(f, "Conditional({condition} ? {then_branch} : {else_branch})")
+            },
+            Expr::CompoundAssign { target, operator, value } => {
+                write!(f, "CompoundAssign({target} {operator} {value})")
+            },
+            Expr::DestructureAssign { pattern, value } => {
+                write!(f, "DestructureAssign({pattern} = {value})")
+            },
             Expr::Grouping { expression } => write!(f, "Grouping({expression})"),
+            Expr::IndexAssign { list, index, value } => {
+                write!(f, "IndexAssign({list}[{index}] = {value})")
+            },
+            Expr::Lambda { keyword: _, params, body } => write!(f, "Lambda({params:?} {body:?})"),
             Expr::List { items } => write!(f, "[{items:?}]"),
-            Expr::ListMethodCall { object, call } => write!(f, "{object}.{call}"),
+            Expr::ListMethodCall { object, call, optional } => {
+                let dot = if *optional { "?." } else { "." };
+                write!(f, "{object}{dot}{call}")
+            },
             Expr::Literal { value } => write!(f, "{value}"),
+            Expr::MethodCall { object, call, optional } => {
+                let dot = if *optional { "?." } else { "." };
+                write!(f, "{object}{dot}{call}")
+            },
             Expr::Logical { left, operator, right } => {
                 write!(f, "Logical({left} {operator} {right})")
             },
-            Expr::Splice { list, is_splice: _, start, end } => {
-                write!(f, "{list}[{start:?}:{end:?}]")
+            Expr::Range { start, operator, end, step } => {
+                write!(f, "Range({start} {operator} {end} {step:?})")
+            },
+            Expr::Splice { list, is_splice: _, start, end, optional } => {
+                let bracket = if *optional { "?[" } else { "[" };
+                write!(f, "{list}{bracket}{start:?}:{end:?}]")
             },
             Expr::Unary { operator, right } => write!(f, "Unary({operator} {right})"),
             Expr::Var { name } => write!(f, "Var({name})"),
@@ -86,4 +157,4 @@ impl fmt::Display for Expr {
     }
 }
 
-expr_visitor!(Alteration, Assign, Binary, Call, Grouping, List, ListMethodCall, Literal, Logical, Splice, Unary, Var);
+expr_visitor!(Alteration, Assign, Binary, Call, CompoundAssign, Conditional, DestructureAssign, Grouping, IndexAssign, Lambda, List, ListMethodCall, Literal, Logical, MethodCall, Range, Splice, Unary, Var);
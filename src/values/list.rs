@@ -1,6 +1,15 @@
-use std::{cmp::min, fmt};
+use std::{
+    cmp::{min, Ordering},
+    fmt,
+};
 
-use crate::{error::InterpreterError, value::{LiteralType, Value}};
+use crate::{
+    callable::Arity,
+    error::InterpreterError,
+    interpreter::Interpreter,
+    number::Number,
+    value::{LiteralType, Value},
+};
 
 const THRESHOLD: f32 = 32.0;
 
@@ -14,6 +23,14 @@ impl List {
         return Self { values };
     }
 
+    /// Whether the list method with this name changes the list it is called on
+    pub fn mutates(method: &str) -> bool {
+        return matches!(
+            method,
+            "push" | "pop" | "remove" | "insertAt" | "sort" | "reverse" | "extend" | "clear"
+        );
+    }
+
     pub fn push(&mut self, args: Vec<Value>) -> Result<&mut List, InterpreterError>  {
         if args.len() != 1 {
             return Err(InterpreterError::ArgsDifferFromArity { args: args.len(), arity: 1 });
@@ -27,28 +44,39 @@ impl List {
     }
 
     pub fn remove(&mut self, args: Vec<Value>) -> Result<(Value, &mut List), InterpreterError> {
-        if args.len() != 1 {
-            return Err(InterpreterError::ArgsDifferFromArity { args: args.len(), arity: 1 });
-        }
+        check_arity(&args, 1)?;
 
-        if let Value::Literal(LiteralType::Num(num)) = args[0] {
-            return Ok((self.values.remove(num as usize), self));
+        let index = self.index_arg(&args[0], self.values.len())?;
+        if index >= self.values.len() {
+            return Err(InterpreterError::IndexOutOfRange);
         }
 
-        return Err(InterpreterError::ExpectedIndexToBeANum);
+        return Ok((self.values.remove(index), self));
     }
 
     pub fn insert_at(&mut self, args: Vec<Value>) -> Result<&mut List, InterpreterError> {
-        if args.len() != 2 {
-            return Err(InterpreterError::ArgsDifferFromArity { args: args.len(), arity: 2 });
-        }
+        check_arity(&args, 2)?;
 
-        if let Value::Literal(LiteralType::Num(num)) = args[0] {
-            self.values.insert(num as usize, args[1].clone());
-            return Ok(self);
+        // Inserting at the length of the list appends the item
+        let index = self.index_arg(&args[0], self.values.len())?;
+        self.values.insert(index, args[1].clone());
+        return Ok(self);
+    }
+
+    /// Converts an index argument to a `usize`, checking that it is an int between 0 and `max`
+    /// INCLUSIVE
+    fn index_arg(&self, value: &Value, max: usize) -> Result<usize, InterpreterError> {
+        if let Value::Literal(LiteralType::Int(num)) = value {
+            if *num < 0 || *num as u64 > max as u64 {
+                return Err(InterpreterError::IndexOutOfRange);
+            }
+            return Ok(*num as usize);
         }
-        
-        return Err(InterpreterError::ExpectedIndexToBeANum);
+        if let Value::Literal(LiteralType::BigInt(_)) = value {
+            return Err(InterpreterError::IndexOutOfRange);
+        }
+
+        return Err(InterpreterError::ExpectedIndexToBeAnInt);
     }
 
     pub fn index(&self, args: Vec<Value>) -> Result<usize, InterpreterError> {
@@ -66,118 +94,481 @@ impl List {
         return self.values.len();
     }
 
-    // https://www.geeksforgeeks.org/timsort/
-    // https://www.baeldung.com/cs/timsort
-    pub fn tim_sort(&mut self) -> Result<List, InterpreterError> {
-        let n = self.values.len();
+    pub fn reverse(&mut self, args: Vec<Value>) -> Result<&mut List, InterpreterError> {
+        check_arity(&args, 0)?;
+        self.values.reverse();
+        return Ok(self);
+    }
+
+    pub fn contains(&self, args: Vec<Value>) -> Result<bool, InterpreterError> {
+        check_arity(&args, 1)?;
+        return Ok(self.values.contains(&args[0]));
+    }
+
+    /// Appends every item of another list to this one
+    pub fn extend(&mut self, args: Vec<Value>) -> Result<&mut List, InterpreterError> {
+        check_arity(&args, 1)?;
+        match &args[0] {
+            Value::List(other) => self.values.extend(other.values.iter().cloned()),
+            _ => return Err(InterpreterError::ExpectedList),
+        }
+        return Ok(self);
+    }
+
+    /// Returns a new list made of this list followed by every list passed in
+    pub fn concat(&self, args: Vec<Value>) -> Result<List, InterpreterError> {
+        let mut values = self.values.clone();
+        for arg in args {
+            match arg {
+                Value::List(other) => values.extend(other.values),
+                _ => return Err(InterpreterError::ExpectedList),
+            }
+        }
+        return Ok(List::new(values));
+    }
+
+    pub fn join(&self, args: Vec<Value>) -> Result<String, InterpreterError> {
+        check_arity(&args, 1)?;
+        let separator = match &args[0] {
+            Value::Literal(LiteralType::Str(s)) => s,
+            _ => return Err(InterpreterError::ExpectedString),
+        };
+
+        let items: Vec<String> = self.values.iter().map(|v| v.to_string()).collect();
+        return Ok(items.join(separator));
+    }
+
+    /// Counts how many items are equal to the argument
+    pub fn count(&self, args: Vec<Value>) -> Result<usize, InterpreterError> {
+        check_arity(&args, 1)?;
+        return Ok(self.values.iter().filter(|v| **v == args[0]).count());
+    }
+
+    pub fn clear(&mut self, args: Vec<Value>) -> Result<&mut List, InterpreterError> {
+        check_arity(&args, 0)?;
+        self.values.clear();
+        return Ok(self);
+    }
+
+    pub fn copy(&self, args: Vec<Value>) -> Result<List, InterpreterError> {
+        check_arity(&args, 0)?;
+        return Ok(self.clone());
+    }
+
+    /// Returns the smallest item, using the same ordering as `sort`
+    pub fn min(&self, args: Vec<Value>) -> Result<Value, InterpreterError> {
+        return self.extreme(args, "min", Ordering::Less);
+    }
+
+    /// Returns the largest item, using the same ordering as `sort`
+    pub fn max(&self, args: Vec<Value>) -> Result<Value, InterpreterError> {
+        return self.extreme(args, "max", Ordering::Greater);
+    }
+
+    fn extreme(&self, args: Vec<Value>, method: &str, wanted: Ordering) -> Result<Value, InterpreterError> {
+        check_arity(&args, 0)?;
+
+        let mut items = self.values.iter();
+        let mut result = match items.next() {
+            Some(first) => first,
+            None => return Err(InterpreterError::EmptyList { method: method.to_string() }),
+        };
+        for value in items {
+            if value.compare(result)? == wanted {
+                result = value;
+            }
+        }
+
+        return Ok(result.clone());
+    }
+
+    /// Adds up the items, which is an int unless one of them is a float
+    pub fn sum(&self, args: Vec<Value>) -> Result<Number, InterpreterError> {
+        check_arity(&args, 0)?;
+
+        let mut total = Number::Int(0);
+        for value in self.values.iter() {
+            match Number::from_value(value) {
+                Some(n) => total = total.add(&n),
+                None => return Err(InterpreterError::ExpectedNumber),
+            }
+        }
+
+        return Ok(total);
+    }
+
+    /// Returns a copy of the list with duplicates removed, keeping the first of each
+    pub fn unique(&self, args: Vec<Value>) -> Result<List, InterpreterError> {
+        check_arity(&args, 0)?;
+
+        let mut values: Vec<Value> = Vec::new();
+        for value in self.values.iter() {
+            if !values.contains(value) {
+                values.push(value.clone());
+            }
+        }
+
+        return Ok(List::new(values));
+    }
+
+    /// Returns a copy of the list with any nested lists flattened by one level
+    pub fn flatten(&self, args: Vec<Value>) -> Result<List, InterpreterError> {
+        check_arity(&args, 0)?;
+
+        let mut values = Vec::new();
+        for value in self.values.iter() {
+            match value {
+                Value::List(list) => values.extend(list.values.iter().cloned()),
+                other => values.push(other.clone()),
+            }
+        }
+
+        return Ok(List::new(values));
+    }
+
+    /// Checks the arguments of a higher-order method, returning the callback that was passed in
+    fn callback(&self, method: &str, args: &[Value], arity: usize) -> Result<Value, InterpreterError> {
+        if args.len() != arity {
+            return Err(InterpreterError::ArgsDifferFromArity { args: args.len(), arity });
+        }
+
+        return match &args[0] {
+            Value::Function(_) | Value::NativeFunction(_) => Ok(args[0].clone()),
+            _ => Err(InterpreterError::ExpectedCallback { method: method.to_string() }),
+        };
+    }
+
+    pub fn map(&self, interpreter: &mut Interpreter, args: Vec<Value>) -> Result<List, InterpreterError> {
+        let callback = self.callback("map", &args, 1)?;
+
+        let mut values = Vec::new();
+        for value in self.values.iter() {
+            values.push(interpreter.call_value(callback.clone(), vec![value.clone()])?);
+        }
+
+        return Ok(List::new(values));
+    }
+
+    pub fn filter(&self, interpreter: &mut Interpreter, args: Vec<Value>) -> Result<List, InterpreterError> {
+        let callback = self.callback("filter", &args, 1)?;
+
+        let mut values = Vec::new();
+        for value in self.values.iter() {
+            let result = interpreter.call_value(callback.clone(), vec![value.clone()])?;
+            if interpreter.is_truthy(&result)? {
+                values.push(value.clone());
+            }
+        }
+
+        return Ok(List::new(values));
+    }
+
+    /// Folds the list into a single value with `callback(accumulator, item)`. Without an initial
+    /// value, the first item is used as the initial value
+    pub fn reduce(&self, interpreter: &mut Interpreter, args: Vec<Value>) -> Result<Value, InterpreterError> {
+        if args.len() != 1 && args.len() != 2 {
+            return Err(InterpreterError::ArgsOutsideArity {
+                name: "reduce".to_string(),
+                args: args.len(),
+                arity: Arity::Range(1, 2),
+            });
+        }
+        let callback = self.callback("reduce", &args[0..1], 1)?;
 
-        let mut run_length = self.calc_min_run(n as f32);
+        let mut items = self.values.iter();
+        let mut accumulator = match args.get(1) {
+            Some(initial) => initial.clone(),
+            None => match items.next() {
+                Some(first) => first.clone(),
+                None => return Err(InterpreterError::ReduceOfEmptyList),
+            },
+        };
 
-        for start in (0..n).step_by(run_length) {
-            let end = min(start + run_length - 1, n - 1);
-            self.insertion_sort(start, end);
+        for value in items {
+            accumulator = interpreter.call_value(callback.clone(), vec![accumulator, value.clone()])?;
         }
 
-        if n <= 32 {
-            return Ok(List::new(self.values.clone()));
+        return Ok(accumulator);
+    }
+
+    pub fn for_each(&self, interpreter: &mut Interpreter, args: Vec<Value>) -> Result<(), InterpreterError> {
+        let callback = self.callback("forEach", &args, 1)?;
+
+        for value in self.values.iter() {
+            interpreter.call_value(callback.clone(), vec![value.clone()])?;
         }
 
-        while run_length < n {
-            for left in (0..n).step_by(2 * run_length) {
-                let mid = min(n - 1, left + run_length - 1);
-                let right = min(n - 1, left + 2 * run_length - 1);
+        return Ok(());
+    }
+
+    pub fn any(&self, interpreter: &mut Interpreter, args: Vec<Value>) -> Result<bool, InterpreterError> {
+        return Ok(self.find_index(interpreter, args, "any")?.is_some());
+    }
+
+    pub fn all(&self, interpreter: &mut Interpreter, args: Vec<Value>) -> Result<bool, InterpreterError> {
+        let callback = self.callback("all", &args, 1)?;
+
+        for value in self.values.iter() {
+            let result = interpreter.call_value(callback.clone(), vec![value.clone()])?;
+            if !interpreter.is_truthy(&result)? {
+                return Ok(false);
+            }
+        }
+
+        return Ok(true);
+    }
+
+    pub fn find(&self, interpreter: &mut Interpreter, args: Vec<Value>) -> Result<Value, InterpreterError> {
+        return match self.find_index(interpreter, args, "find")? {
+            Some(index) => Ok(self.values[index].clone()),
+            None => Ok(Value::Literal(LiteralType::Null)),
+        };
+    }
+
+    /// Returns the index of the first item that the callback returns a truthy value for
+    pub fn find_index(
+        &self,
+        interpreter: &mut Interpreter,
+        args: Vec<Value>,
+        method: &str,
+    ) -> Result<Option<usize>, InterpreterError> {
+        let callback = self.callback(method, &args, 1)?;
 
-                if mid < right {
-                    self.merge_sort(left, mid, right)?;
-                }
+        for (i, value) in self.values.iter().enumerate() {
+            let result = interpreter.call_value(callback.clone(), vec![value.clone()])?;
+            if interpreter.is_truthy(&result)? {
+                return Ok(Some(i));
             }
-            run_length *= 2;
         }
-        
-        return Ok(List::new(self.values.clone()));
+
+        return Ok(None);
     }
 
-    fn calc_min_run(&self, len: f32) -> usize {
-        let mut run_len = len;
-        let mut remainder: f32 = 0.0;
-        while run_len > THRESHOLD {
-            if run_len % 2.0 == 1.0 {
-                remainder = 1.0;
+    /// Maps each item and flattens any lists returned by the callback by one level
+    pub fn flat_map(&self, interpreter: &mut Interpreter, args: Vec<Value>) -> Result<List, InterpreterError> {
+        let callback = self.callback("flatMap", &args, 1)?;
+
+        let mut values = Vec::new();
+        for value in self.values.iter() {
+            match interpreter.call_value(callback.clone(), vec![value.clone()])? {
+                Value::List(list) => values.extend(list.values),
+                other => values.push(other),
             }
-            run_len = run_len.floor() / 2.0;
         }
-        
-        return (run_len + remainder) as usize;
+
+        return Ok(List::new(values));
+    }
+
+    /// Returns a copy of the list, stably sorted by the key that the callback returns for each item
+    pub fn sort_by(&self, interpreter: &mut Interpreter, args: Vec<Value>) -> Result<List, InterpreterError> {
+        let callback = self.callback("sortBy", &args, 1)?;
+
+        let mut sorted = self.clone();
+        sorted.sort_with(interpreter, None, Some(callback), false)?;
+        return Ok(sorted);
+    }
+
+    /// Sorts the list in place. See `sort_options` for the arguments it accepts
+    pub fn sort(&mut self, interpreter: &mut Interpreter, args: Vec<Value>) -> Result<&mut List, InterpreterError> {
+        let (comparator, key, descending) = self.sort_options(args)?;
+        self.sort_with(interpreter, comparator, key, descending)?;
+        return Ok(self);
     }
 
-    fn insertion_sort(&mut self, left: usize, right: usize) {
-        let mut j;
-        for i in left + 1..right + 1 {
-            j = i;
-            while j > left && self.values[j] < self.values[j - 1] {
-                self.values.swap(j, j - 1);
-                j -= 1;
+    /// Returns a sorted copy of the list, leaving the list itself unchanged
+    pub fn sorted(&self, interpreter: &mut Interpreter, args: Vec<Value>) -> Result<List, InterpreterError> {
+        let mut sorted = self.clone();
+        sorted.sort(interpreter, args)?;
+        return Ok(sorted);
+    }
+
+    /// Reads the arguments of `sort` and `sorted`, which are an optional comparator, key function
+    /// and descending flag, in that order. `null` skips the comparator or key, and a bool on its
+    /// own is taken as the descending flag, e.g. `sort(true)` or `sort(null, key, true)`
+    fn sort_options(&self, mut args: Vec<Value>) -> Result<(Option<Value>, Option<Value>, bool), InterpreterError> {
+        if args.len() > 3 {
+            return Err(InterpreterError::ArgsDifferFromArity { args: args.len(), arity: 3 });
+        }
+
+        let descending = match args.last() {
+            Some(Value::Literal(LiteralType::True)) => {
+                args.pop();
+                true
+            },
+            Some(Value::Literal(LiteralType::False)) => {
+                args.pop();
+                false
+            },
+            _ => false,
+        };
+
+        if args.len() > 2 {
+            return Err(InterpreterError::ExpectedSortFlag);
+        }
+
+        let mut options = Vec::new();
+        for arg in args {
+            match arg {
+                Value::Function(_) | Value::NativeFunction(_) => options.push(Some(arg)),
+                Value::Literal(LiteralType::Null) => options.push(None),
+                _ => return Err(InterpreterError::ExpectedCallback { method: "sort".to_string() }),
             }
         }
+        options.resize(2, None);
+
+        return Ok((options[0].clone(), options[1].clone(), descending));
     }
 
-    fn merge_sort(&mut self, l: usize, m: usize, r: usize) -> Result<(), InterpreterError> {
-        let left_len = m - l + 1;
-        let right_len = r - m;
-
-        let left = self.values[l..=m].to_vec();
-        let right = self.values[m+1..=r].to_vec();
-
-
-        let mut i = 0;
-        let mut j = 0;
-        let mut k = l;
-
-        while i < left_len && j < right_len {
-            match (&left[i], &right[j]) {
-                (Value::Literal(a), Value::Literal(b)) => {
-                    match (a, b) {
-                        (LiteralType::Num(n1), LiteralType::Num(n2)) => {
-                            if n1 <= n2 {
-                                self.values[k] = left[i].clone();
-                                i += 1;
-                            } else {
-                                self.values[k] = right[j].clone();
-                                j += 1;
-                            }
-                            k += 1;
-                        },
-                        (LiteralType::Str(s1), LiteralType::Str(s2)) => {
-                            if s1 <= s2 {
-                                self.values[k] = left[i].clone();
-                                i += 1;
-                            } else {
-                                self.values[k] = right[j].clone();
-                                j += 1;
-                            }
-                            k += 1;
-                        },
-                        _ => return Err(InterpreterError::CannotCompareValues),
+    /// Stably sorts the list. Each item's key is worked out once, and keys are ordered by the
+    /// comparator if there is one, which must return a negative, zero or positive number, or by
+    /// `Value::compare` otherwise
+    fn sort_with(
+        &mut self,
+        interpreter: &mut Interpreter,
+        comparator: Option<Value>,
+        key: Option<Value>,
+        descending: bool,
+    ) -> Result<(), InterpreterError> {
+        let mut keyed = Vec::new();
+        for value in self.values.iter() {
+            let k = match &key {
+                Some(key) => interpreter.call_value(key.clone(), vec![value.clone()])?,
+                None => value.clone(),
+            };
+            keyed.push((k, value.clone()));
+        }
+
+        tim_sort(&mut keyed, &mut |(a, _), (b, _)| {
+            let ordering = match &comparator {
+                Some(comparator) => {
+                    let result = interpreter.call_value(comparator.clone(), vec![a.clone(), b.clone()])?;
+                    match Number::from_value(&result) {
+                        Some(n) => n.partial_cmp(&Number::Int(0)).unwrap_or(Ordering::Equal),
+                        None => return Err(InterpreterError::ExpectedComparatorNumber),
                     }
                 },
-                _ => return Err(InterpreterError::CannotCompareValues),
+                None => a.compare(b)?,
+            };
+
+            if descending {
+                return Ok(ordering.reverse());
             }
+            return Ok(ordering);
+        })?;
+
+        self.values = keyed.into_iter().map(|(_, value)| value).collect();
+        return Ok(());
+    }
+}
+
+fn check_arity(args: &[Value], arity: usize) -> Result<(), InterpreterError> {
+    if args.len() != arity {
+        return Err(InterpreterError::ArgsDifferFromArity { args: args.len(), arity });
+    }
+    return Ok(());
+}
+
+// https://www.geeksforgeeks.org/timsort/
+// https://www.baeldung.com/cs/timsort
+fn tim_sort<T, F>(values: &mut [T], compare: &mut F) -> Result<(), InterpreterError>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Result<Ordering, InterpreterError>,
+{
+    let n = values.len();
+    if n == 0 {
+        return Ok(());
+    }
+
+    let mut run_length = calc_min_run(n as f32);
+
+    for start in (0..n).step_by(run_length) {
+        let end = min(start + run_length - 1, n - 1);
+        insertion_sort(values, start, end, compare)?;
+    }
+
+    while run_length < n {
+        for left in (0..n).step_by(2 * run_length) {
+            let mid = min(n - 1, left + run_length - 1);
+            let right = min(n - 1, left + 2 * run_length - 1);
+
+            if mid < right {
+                merge(values, left, mid, right, compare)?;
+            }
+        }
+        run_length *= 2;
+    }
+
+    return Ok(());
+}
+
+fn calc_min_run(len: f32) -> usize {
+    let mut run_len = len;
+    let mut remainder: f32 = 0.0;
+    while run_len > THRESHOLD {
+        if run_len % 2.0 == 1.0 {
+            remainder = 1.0;
         }
+        run_len = run_len.floor() / 2.0;
+    }
 
-        while i < left_len {
-            self.values[k] = left[i].clone();
-            i += 1;
-            k += 1;
+    return (run_len + remainder) as usize;
+}
+
+fn insertion_sort<T, F>(values: &mut [T], left: usize, right: usize, compare: &mut F) -> Result<(), InterpreterError>
+where
+    F: FnMut(&T, &T) -> Result<Ordering, InterpreterError>,
+{
+    let mut j;
+    for i in left + 1..right + 1 {
+        j = i;
+        // Only swapping strictly smaller items keeps equal items in their original order
+        while j > left && compare(&values[j], &values[j - 1])? == Ordering::Less {
+            values.swap(j, j - 1);
+            j -= 1;
         }
+    }
 
-        while j < right_len {
-            self.values[k] = right[j].clone();
+    return Ok(());
+}
+
+fn merge<T, F>(values: &mut [T], l: usize, m: usize, r: usize, compare: &mut F) -> Result<(), InterpreterError>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Result<Ordering, InterpreterError>,
+{
+    let left = values[l..=m].to_vec();
+    let right = values[m+1..=r].to_vec();
+
+    let mut i = 0;
+    let mut j = 0;
+    let mut k = l;
+
+    while i < left.len() && j < right.len() {
+        // Taking from the left run when items are equal keeps the sort stable
+        if compare(&left[i], &right[j])? != Ordering::Greater {
+            values[k] = left[i].clone();
+            i += 1;
+        } else {
+            values[k] = right[j].clone();
             j += 1;
-            k += 1;
         }
+        k += 1;
+    }
 
-        return Ok(());
+    while i < left.len() {
+        values[k] = left[i].clone();
+        i += 1;
+        k += 1;
+    }
+
+    while j < right.len() {
+        values[k] = right[j].clone();
+        j += 1;
+        k += 1;
     }
+
+    return Ok(());
 }
 
 impl fmt::Display for List {
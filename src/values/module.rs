@@ -0,0 +1,60 @@
+use std::{fmt, rc::Rc};
+
+use crate::{
+    interpreter::Env,
+    value::Value,
+};
+
+/// A file loaded with `import`, which has its own global environment. Importing the same file
+/// again gives the same module, so its top level only runs once.
+#[derive(Clone)]
+pub struct Module {
+    name: String, // The path the module was imported with, used in error messages
+    environment: Env,
+    exports: Rc<Vec<String>>, // The names declared with 'export', which are the only ones that can be used outside it
+}
+
+impl Module {
+    pub fn new(name: String, environment: Env, exports: Vec<String>) -> Self {
+        return Self { name, environment, exports: Rc::new(exports) };
+    }
+
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+
+    /// Looks up an exported value, giving `None` if the module does not export `member`
+    pub fn get(&self, member: &str) -> Option<Value> {
+        if !self.exports.iter().any(|e| e == member) {
+            return None;
+        }
+        return self.environment.borrow().get_value(member);
+    }
+}
+
+impl PartialEq for Module {
+    fn eq(&self, other: &Self) -> bool {
+        return Rc::ptr_eq(&self.environment, &other.environment);
+    }
+}
+
+impl PartialOrd for Module {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        if self == other {
+            return Some(std::cmp::Ordering::Equal);
+        }
+        return None;
+    }
+}
+
+impl fmt::Debug for Module {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "Module({})", self.name);
+    }
+}
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "Module({})", self.name);
+    }
+}
@@ -0,0 +1,202 @@
+use std::cmp::Ordering;
+
+use crate::{
+    bigint::BigInt,
+    error::InterpreterError,
+    value::{LiteralType, Value},
+};
+
+/// A numeric value taken out of a literal, used to carry out arithmetic.
+///
+/// Integer operations are checked, and promote to a `BigInt` when the result does not fit in an
+/// `i64`. Whenever a float is involved, the other operand is converted to a float as well.
+#[derive(Clone, Debug)]
+pub enum Number {
+    Int(i64),
+    Big(BigInt),
+    Float(f64),
+}
+
+impl Number {
+    pub fn from_literal(literal: &LiteralType) -> Option<Self> {
+        return match literal {
+            LiteralType::Int(n) => Some(Number::Int(*n)),
+            LiteralType::BigInt(n) => Some(Number::Big(n.clone())),
+            LiteralType::Num(n) => Some(Number::Float(*n)),
+            _ => None,
+        };
+    }
+
+    pub fn from_value(value: &Value) -> Option<Self> {
+        return match value {
+            Value::Literal(literal) => Self::from_literal(literal),
+            _ => None,
+        };
+    }
+
+    /// Converts back into a literal, turning a `BigInt` back into an `i64` if it fits in one
+    pub fn into_literal(self) -> LiteralType {
+        return match self {
+            Number::Int(n) => LiteralType::Int(n),
+            Number::Big(n) => match n.to_i64() {
+                Some(n) => LiteralType::Int(n),
+                None => LiteralType::BigInt(n),
+            },
+            Number::Float(n) => LiteralType::Num(n),
+        };
+    }
+
+    pub fn into_value(self) -> Value {
+        return Value::Literal(self.into_literal());
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        return match self {
+            Number::Int(n) => *n as f64,
+            Number::Big(n) => n.to_f64(),
+            Number::Float(n) => *n,
+        };
+    }
+
+    pub fn is_zero(&self) -> bool {
+        return match self {
+            Number::Int(n) => *n == 0,
+            Number::Big(n) => n.is_zero(),
+            Number::Float(n) => *n == 0.0,
+        };
+    }
+
+    pub fn is_float(&self) -> bool {
+        return matches!(self, Number::Float(_));
+    }
+
+    fn to_big(&self) -> BigInt {
+        return match self {
+            Number::Int(n) => BigInt::from_i64(*n),
+            Number::Big(n) => n.clone(),
+            Number::Float(_) => unreachable!("floats are never promoted to a BigInt"),
+        };
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        return self.integer_op(other, i64::checked_add, BigInt::add, |a, b| a + b);
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        return self.integer_op(other, i64::checked_sub, BigInt::sub, |a, b| a - b);
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        return self.integer_op(other, i64::checked_mul, BigInt::mul, |a, b| a * b);
+    }
+
+    /// Division with `/`, which always gives a float
+    pub fn div(&self, other: &Self) -> Self {
+        return Number::Float(self.to_f64() / other.to_f64());
+    }
+
+    /// The remainder of dividing by `other`, which has the same sign as `self`. Dividing an
+    /// integer by zero follows the floating point rules
+    pub fn rem(&self, other: &Self) -> Self {
+        if other.is_zero() {
+            return Number::Float(self.to_f64() % other.to_f64());
+        }
+        return self.integer_op(
+            other,
+            i64::checked_rem,
+            |a, b| a.div_rem(b).map(|(_, r)| r).unwrap_or_else(BigInt::zero),
+            |a, b| a % b,
+        );
+    }
+
+    /// Division with `//`, which rounds down. Dividing an integer by zero follows the floating
+    /// point rules
+    pub fn floor_div(&self, other: &Self) -> Self {
+        if other.is_zero() {
+            return Number::Float((self.to_f64() / other.to_f64()).floor());
+        }
+        return self.integer_op(
+            other,
+            |a, b| {
+                let quotient = a.checked_div(b)?;
+                if a % b != 0 && (a < 0) != (b < 0) {
+                    return Some(quotient - 1);
+                }
+                return Some(quotient);
+            },
+            |a, b| a.div_floor(b).unwrap_or_else(BigInt::zero),
+            |a, b| (a / b).floor(),
+        );
+    }
+
+    /// Raises to a power. A negative integer exponent gives a float, like dividing does
+    pub fn pow(&self, other: &Self) -> Result<Self, InterpreterError> {
+        if self.is_float() || other.is_float() {
+            return Ok(Number::Float(self.to_f64().powf(other.to_f64())));
+        }
+
+        let exponent = match other {
+            Number::Int(n) if *n >= 0 => *n as u64,
+            _ => return Ok(Number::Float(self.to_f64().powf(other.to_f64()))),
+        };
+
+        if let Number::Int(base) = self {
+            if let Some(result) = u32::try_from(exponent).ok().and_then(|e| base.checked_pow(e)) {
+                return Ok(Number::Int(result));
+            }
+        }
+        return match self.to_big().pow(exponent) {
+            Some(result) => Ok(Number::Big(result)),
+            None => Err(InterpreterError::ExponentTooLarge { exponent }),
+        };
+    }
+
+    pub fn neg(&self) -> Self {
+        return match self {
+            Number::Int(n) => match n.checked_neg() {
+                Some(n) => Number::Int(n),
+                None => Number::Big(BigInt::from_i64(*n).neg()),
+            },
+            Number::Big(n) => Number::Big(n.neg()),
+            Number::Float(n) => Number::Float(-n),
+        };
+    }
+
+    /// Runs the integer version of an operation if neither side is a float, falling back to a
+    /// `BigInt` if the `i64` version overflows
+    fn integer_op(
+        &self,
+        other: &Self,
+        small: impl Fn(i64, i64) -> Option<i64>,
+        big: impl Fn(&BigInt, &BigInt) -> BigInt,
+        float: impl Fn(f64, f64) -> f64,
+    ) -> Self {
+        if self.is_float() || other.is_float() {
+            return Number::Float(float(self.to_f64(), other.to_f64()));
+        }
+
+        if let (Number::Int(a), Number::Int(b)) = (self, other) {
+            if let Some(result) = small(*a, *b) {
+                return Number::Int(result);
+            }
+        }
+        return Number::Big(big(&self.to_big(), &other.to_big()));
+    }
+}
+
+/// Numbers are equal if they have the same value, whatever their type, so `1 == 1.0`
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        return self.partial_cmp(other) == Some(Ordering::Equal);
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return match (self, other) {
+            (Number::Int(a), Number::Int(b)) => Some(a.cmp(b)),
+            (Number::Float(_), _) | (_, Number::Float(_)) => self.to_f64().partial_cmp(&other.to_f64()),
+            _ => Some(self.to_big().cmp(&other.to_big())),
+        };
+    }
+}
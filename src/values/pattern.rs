@@ -0,0 +1,224 @@
+use std::fmt;
+
+use crate::{
+    error::InterpreterError,
+    expr::Expr,
+    list::List,
+    stmt::Stmt,
+    token::Token,
+    value::{LiteralType, Value},
+};
+
+/// The shape that a value is checked against by a `match` statement or a destructuring
+/// assignment, which can bind parts of the value to variables
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pattern {
+    Binding {
+        name: Token, // Matches any value, binding it to this name
+    },
+    List {
+        items: Vec<Pattern>, // Patterns for the first items of the list
+        rest: Option<Option<Token>>, // Set by '...', which matches any remaining items, binding them if it has a name
+    },
+    Literal {
+        value: LiteralType,
+    },
+    Or {
+        alternatives: Vec<Pattern>, // Patterns separated by '|', any of which can match
+    },
+    Wildcard {
+        token: Token, // The '_' token, which matches any value without binding it
+    },
+}
+
+/// A single arm of a `match` statement
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>, // Given with 'if', which must also be truthy for the arm to run
+    pub body: Stmt,
+}
+
+impl Pattern {
+    /// Checks whether the value matches the pattern, adding any variables it binds to `bindings`
+    pub fn matches(&self, value: &Value, bindings: &mut Vec<(Token, Value)>) -> bool {
+        match self {
+            Pattern::Binding { name } => {
+                bindings.push((name.clone(), value.clone()));
+                return true;
+            }
+            Pattern::Wildcard { .. } => return true,
+            Pattern::Literal { value: literal } => {
+                return *value == Value::Literal(literal.clone());
+            }
+            Pattern::Or { alternatives } => {
+                for alternative in alternatives {
+                    let mut alternative_bindings = Vec::new();
+                    if alternative.matches(value, &mut alternative_bindings) {
+                        bindings.extend(alternative_bindings);
+                        return true;
+                    }
+                }
+                return false;
+            }
+            Pattern::List { items, rest } => {
+                let list = match value {
+                    Value::List(list) => list,
+                    _ => return false,
+                };
+
+                let length_matches = match rest {
+                    Some(_) => list.len() >= items.len(),
+                    None => list.len() == items.len(),
+                };
+                if !length_matches {
+                    return false;
+                }
+
+                for (item, value) in items.iter().zip(list.values.iter()) {
+                    if !item.matches(value, bindings) {
+                        return false;
+                    }
+                }
+
+                if let Some(Some(name)) = rest {
+                    let remaining = List::new(list.values[items.len()..].to_vec());
+                    bindings.push((name.clone(), Value::List(remaining)));
+                }
+                return true;
+            }
+        }
+    }
+
+    /// Binds the parts of the value to the variables in the pattern, like `matches`, but gives an
+    /// error saying which element of the value did not fit the pattern. `path` describes where the
+    /// value came from, such as `value[1]`
+    pub fn destructure(
+        &self,
+        value: &Value,
+        path: &str,
+        bindings: &mut Vec<(Token, Value)>,
+    ) -> Result<(), InterpreterError> {
+        let failed = |expected: String, got: String| InterpreterError::DestructuringFailed {
+            element: path.to_string(),
+            expected,
+            got,
+        };
+
+        match self {
+            Pattern::List { items, rest } => {
+                let list = match value {
+                    Value::List(list) => list,
+                    _ => return Err(failed("a list".to_string(), value.type_name().to_string())),
+                };
+
+                let length_matches = match rest {
+                    Some(_) => list.len() >= items.len(),
+                    None => list.len() == items.len(),
+                };
+                if !length_matches {
+                    let at_least = if rest.is_some() { "at least " } else { "" };
+                    return Err(failed(
+                        format!("a list of {at_least}{} items", items.len()),
+                        format!("a list of {} items", list.len()),
+                    ));
+                }
+
+                for (i, (item, value)) in items.iter().zip(list.values.iter()).enumerate() {
+                    item.destructure(value, &format!("{path}[{i}]"), bindings)?;
+                }
+
+                if let Some(Some(name)) = rest {
+                    let remaining = List::new(list.values[items.len()..].to_vec());
+                    bindings.push((name.clone(), Value::List(remaining)));
+                }
+                return Ok(());
+            }
+            _ => {
+                if !self.matches(value, bindings) {
+                    return Err(failed(format!("'{self}'"), value.to_string()));
+                }
+                return Ok(());
+            }
+        }
+    }
+
+    /// The names of every variable that the pattern binds, in order
+    pub fn bindings(&self) -> Vec<Token> {
+        return match self {
+            Pattern::Binding { name } => vec![name.clone()],
+            Pattern::List { items, rest } => {
+                let mut names: Vec<Token> = items.iter().flat_map(|p| p.bindings()).collect();
+                if let Some(Some(name)) = rest {
+                    names.push(name.clone());
+                }
+                names
+            }
+            // Every alternative binds the same names, which is checked by the semantic analyser
+            Pattern::Or { alternatives } => alternatives[0].bindings(),
+            Pattern::Literal { .. } | Pattern::Wildcard { .. } => Vec::new(),
+        };
+    }
+
+    /// Whether the pattern matches every value
+    pub fn is_irrefutable(&self) -> bool {
+        return match self {
+            Pattern::Binding { .. } | Pattern::Wildcard { .. } => true,
+            Pattern::Or { alternatives } => alternatives.iter().any(|p| p.is_irrefutable()),
+            Pattern::List { .. } | Pattern::Literal { .. } => false,
+        };
+    }
+
+    /// Whether every value that matches `other` also matches this pattern, which means that an
+    /// arm with `other` after an arm with this pattern can never run
+    pub fn covers(&self, other: &Pattern) -> bool {
+        if self.is_irrefutable() {
+            return true;
+        }
+
+        match (self, other) {
+            (Pattern::Or { alternatives }, _) => {
+                return alternatives.iter().any(|p| p.covers(other));
+            }
+            (_, Pattern::Or { alternatives }) => {
+                return alternatives.iter().all(|p| self.covers(p));
+            }
+            (Pattern::Literal { value: a }, Pattern::Literal { value: b }) => return a == b,
+            (
+                Pattern::List { items: a_items, rest: a_rest },
+                Pattern::List { items: b_items, rest: b_rest },
+            ) => {
+                let lengths_covered = match a_rest {
+                    Some(_) => b_items.len() >= a_items.len(),
+                    None => b_rest.is_none() && b_items.len() == a_items.len(),
+                };
+                return lengths_covered && a_items.iter().zip(b_items.iter()).all(|(a, b)| a.covers(b));
+            }
+            _ => return false,
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Pattern::Binding { name } => write!(f, "{}", name.lexeme),
+            Pattern::List { items, rest } => {
+                let mut parts: Vec<String> = items.iter().map(|p| p.to_string()).collect();
+                match rest {
+                    Some(Some(name)) => parts.push(format!("...{}", name.lexeme)),
+                    Some(None) => parts.push("...".to_string()),
+                    None => {}
+                }
+                write!(f, "[{}]", parts.join(", "))
+            }
+            Pattern::Literal { value: LiteralType::Str(s) } => write!(f, "\"{s}\""),
+            Pattern::Literal { value } => write!(f, "{value}"),
+            Pattern::Or { alternatives } => {
+                let parts: Vec<String> = alternatives.iter().map(|p| p.to_string()).collect();
+                write!(f, "{}", parts.join(" | "))
+            }
+            Pattern::Wildcard { .. } => write!(f, "_"),
+        };
+    }
+}
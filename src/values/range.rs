@@ -0,0 +1,149 @@
+use std::fmt;
+
+use crate::{
+    error::InterpreterError,
+    list::List,
+    number::Number,
+    value::{LiteralType, Value},
+};
+
+/// A lazy sequence of numbers created with `start..end` or `start..=end`, with an optional step
+/// given as `start..end..step`.
+///
+/// The items are worked out when they are needed rather than being stored, so a range can be
+/// indexed and iterated over without building a list. The items are ints if the start, end and
+/// step are all ints, and floats otherwise.
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub struct Range {
+    pub bounds: Bounds,
+    pub inclusive: bool,
+}
+
+/// The start, end and step of a range. Ints are kept as ints so that their items stay exact past
+/// the point where a float can no longer hold every whole number
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub enum Bounds {
+    Int { start: i64, end: i64, step: i64 },
+    Float { start: f64, end: f64, step: f64 },
+}
+
+impl Range {
+    /// Creates a new range. Without a step, the range counts up by 1, or down by 1 if `end` is
+    /// smaller than `start`
+    pub fn new(start: Number, end: Number, step: Option<Number>, inclusive: bool) -> Result<Self, InterpreterError> {
+        if let (Number::Int(start), Number::Int(end), None | Some(Number::Int(_))) = (&start, &end, &step) {
+            let step = match step {
+                Some(Number::Int(step)) => step,
+                _ if end < start => -1,
+                _ => 1,
+            };
+            if step == 0 {
+                return Err(InterpreterError::InvalidRange);
+            }
+
+            let bounds = Bounds::Int { start: *start, end: *end, step };
+            return Ok(Self { bounds, inclusive });
+        }
+
+        let (start, end) = (start.to_f64(), end.to_f64());
+        let step = match step {
+            Some(step) => step.to_f64(),
+            None if end < start => -1.0,
+            None => 1.0,
+        };
+
+        if step == 0.0 || !step.is_finite() || !start.is_finite() || !end.is_finite() {
+            return Err(InterpreterError::InvalidRange);
+        }
+
+        return Ok(Self { bounds: Bounds::Float { start, end, step }, inclusive });
+    }
+
+    /// The number of items in the range, which is 0 if the step goes away from the end. A range
+    /// with more items than can be counted is an error
+    pub fn len(&self) -> Result<usize, InterpreterError> {
+        return self.count().ok_or(InterpreterError::RangeTooLarge);
+    }
+
+    /// The number of items in the range, or `None` if there are too many to fit in a `usize`
+    fn count(&self) -> Option<usize> {
+        match self.bounds {
+            Bounds::Int { start, end, step } => {
+                // Worked out in i128, where the distance between any two i64s fits
+                let distance = end as i128 - start as i128;
+                if distance != 0 && (distance < 0) != (step < 0) {
+                    return Some(0);
+                }
+
+                let (distance, step) = (distance.abs(), (step as i128).abs());
+                let count = match self.inclusive {
+                    true => distance / step + 1,
+                    false => (distance + step - 1) / step,
+                };
+                return usize::try_from(count).ok();
+            }
+            Bounds::Float { start, end, step } => {
+                let steps = (end - start) / step;
+                if steps < 0.0 {
+                    return Some(0);
+                }
+
+                let count = match self.inclusive {
+                    true => steps.floor() + 1.0,
+                    false => steps.ceil(),
+                };
+                // Also catches a distance too large for a float, which makes `steps` infinite
+                if count >= usize::MAX as f64 {
+                    return None;
+                }
+                return Some(count as usize);
+            }
+        }
+    }
+
+    /// Returns the item at `index` as a value, which is an int if the range is made of ints, or
+    /// `None` if it is past the end of the range
+    pub fn item(&self, index: usize) -> Option<Value> {
+        // A range too large to count has more items than any index can reach
+        if self.count().is_some_and(|count| index >= count) {
+            return None;
+        }
+
+        match self.bounds {
+            Bounds::Int { start, step, .. } => {
+                let n = start as i128 + step as i128 * index as i128;
+                return Some(Value::Literal(LiteralType::Int(n as i64)));
+            }
+            Bounds::Float { start, step, .. } => {
+                return Some(Value::Literal(LiteralType::Num(start + step * index as f64)));
+            }
+        }
+    }
+
+    pub fn to_list(&self) -> Result<List, InterpreterError> {
+        let values = (0..self.len()?).filter_map(|i| self.item(i)).collect();
+        return Ok(List::new(values));
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operator = if self.inclusive { "..=" } else { ".." };
+        match self.bounds {
+            Bounds::Int { start, end, step } => {
+                write!(f, "{start}{operator}{end}")?;
+                if step != if end < start { -1 } else { 1 } {
+                    write!(f, "..{step}")?;
+                }
+            }
+            Bounds::Float { start, end, step } => {
+                write!(f, "{start:?}{operator}{end:?}")?;
+                if step != if end < start { -1.0 } else { 1.0 } {
+                    write!(f, "..{step:?}")?;
+                }
+            }
+        }
+
+        return Ok(());
+    }
+}
@@ -3,6 +3,7 @@ use std::fmt;
 
 use crate::{
     expr::Expr,
+    pattern::{MatchArm, Pattern},
     stmt_visitor,
     token::Token
 };
@@ -12,6 +13,14 @@ pub enum Stmt {
     Block {
         statements: Vec<Stmt>,
     },
+    Destructure {
+        pattern: Pattern, // Binds each variable in the pattern to part of the initializer
+        initializer: Expr,
+        constant: bool, // Declared with 'const', so the variables cannot be reassigned
+    },
+    Export {
+        declaration: Box<Stmt>, // A variable, constant or function declaration
+    },
     Expression {
         expression: Expr,
     },
@@ -21,9 +30,14 @@ pub enum Stmt {
         increment: Option<Expr>,
         body: Box<Stmt>,
     },
+    ForIn {
+        name: Token,
+        iterable: Expr,
+        body: Box<Stmt>,
+    },
     Function {
         name: Token,
-        params: Vec<Token>,
+        params: Vec<Parameter>,
         body: Vec<Stmt>,
     },
     If {
@@ -31,8 +45,20 @@ pub enum Stmt {
         then_branch: Box<Stmt>,
         else_branch: Option<Box<Stmt>>,
     },
+    Import {
+        keyword: Token, // The 'import' or 'from' token, used to locate the statement
+        path: Token, // The string token of the file's path
+        alias: Option<Token>, // The name given with 'as', which the module is bound to
+        names: Vec<Token>, // The exported names given after 'from ... import', which are bound directly
+    },
+    Match {
+        keyword: Token, // The 'match' token, used to locate the statement
+        value: Expr,
+        arms: Vec<MatchArm>, // Checked in order, running the first that matches
+    },
     Print {
-        expression: Expr,
+        keyword: Token, // The 'print' or 'eprint' token, which decides where the values are written
+        expressions: Vec<Expr>,
     },
     Return {
         keyword: Token,
@@ -41,21 +67,42 @@ pub enum Stmt {
     Var {
         name: Token,
         initializer: Option<Expr>,
+        constant: bool, // Declared with 'const', so the variable cannot be reassigned
     },
     While {
         condition: Expr,
         body: Box<Stmt>,
     },
+    Yield {
+        keyword: Token,
+        value: Expr,
+    },
+}
+
+/// A parameter of a function or lambda
+#[derive(Clone, Debug, PartialEq)]
+pub struct Parameter {
+    pub name: Token,
+    pub default: Option<Expr>, // Evaluated when the function is called without this argument
+    pub variadic: bool, // Set by '...', which collects any remaining positional arguments in a list
 }
 
 impl fmt::Display for Stmt {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Stmt::Block { statements } => write!(f, "Block({statements:?}"),
+            Stmt::Destructure { pattern, initializer, constant } => {
+                let keyword = if *constant { "const" } else { "var" };
+                write!(f, "Destructure({keyword} {pattern} {initializer})")
+            },
+            Stmt::Export { declaration } => write!(f, "Export({declaration})"),
             Stmt::Expression { expression } => write!(f, "Expression({expression})"),
             Stmt::For { initializer, condition, increment, body } => {
                 return write!(f, "For({initializer:?} {condition} {increment:?} {body})");
             },
+            Stmt::ForIn { name, iterable, body } => {
+                return write!(f, "ForIn({name} {iterable} {body})");
+            },
             Stmt::Function { name, params, body } => {
                 return write!(f, "Function({name} {params:?} {body:?})")
             },
@@ -70,9 +117,22 @@ impl fmt::Display for Stmt {
                     return write!(f, "If({condition} {then_branch})");
                 }
             },
-            Stmt::Print { expression } => write!(f, "Print({expression})"),
+            Stmt::Import { keyword: _, path, alias, names } => {
+                return write!(f, "Import({} {alias:?} {names:?})", path.literal);
+            },
+            Stmt::Match { keyword: _, value, arms } => {
+                let patterns: Vec<String> = arms.iter().map(|arm| arm.pattern.to_string()).collect();
+                return write!(f, "Match({value} {patterns:?})");
+            },
+            Stmt::Print { keyword, expressions } => {
+                let values: Vec<String> = expressions.iter().map(|e| e.to_string()).collect();
+                return write!(f, "{}({})", keyword.token_type, values.join(", "));
+            },
             Stmt::Return { keyword: _, value } => return write!(f, "Return({value:?})"),
-            Stmt::Var { name, initializer } => {
+            Stmt::Var { name, initializer, constant: true } => {
+                return write!(f, "Const({name} {initializer:?})");
+            }
+            Stmt::Var { name, initializer, constant: false } => {
                 if initializer.is_some() {
                     return write!(f, "Var({name} {}", initializer.as_ref().unwrap());
                 } else {
@@ -80,8 +140,9 @@ impl fmt::Display for Stmt {
                 }
             }
             Stmt::While { condition, body } => return write!(f, "While({condition} {body})"),
+            Stmt::Yield { keyword: _, value } => return write!(f, "Yield({value})"),
         }
     }
 }
 
-stmt_visitor!(Block, Expression, For, Function, If, Print, Return, Var, While);
+stmt_visitor!(Block, Destructure, Export, Expression, For, ForIn, Function, If, Import, Match, Print, Return, Var, While, Yield);
@@ -2,16 +2,20 @@ use std::fmt;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TokenType {
-    LParen, RParen, LBrace, RBrace, LBrack, RBrack, Comma, Dot,
+    LParen, RParen, LBrace, RBrace, LBrack, RBrack, Comma, Dot, DotDot, DotDotEqual, DotDotDot,
     Minus, Plus, Semicolon, Colon, FSlash, Asterisk, Incr, Decr,
+    Percent, AsteriskAsterisk, FSlashFSlash,
+    Ampersand, Pipe, Caret, Tilde, LessLess, GreaterGreater,
+    Question, QuestionQuestion, QuestionDot, QuestionLBrack,
 
-    Bang, BangEqual, Equal, EqualEqual,
+    Bang, BangEqual, Equal, EqualEqual, Arrow,
+    PlusEqual, MinusEqual, AsteriskEqual, FSlashEqual, PercentEqual,
     Greater,GreaterEqual, Less, LessEqual,
 
-    Identifier, String, Num,
+    Identifier, String, Int, Num,
 
-    And, Class, Def, Else, False, For, If, Null, 
-    Or, Print, Return, Super, This, True, Var, While,
+    And, As, Class, Const, Def, Else, Eprint, Export, False, For, From, If, Import, In, Match, Null, 
+    Or, Print, Return, Super, This, True, Var, While, Yield,
 
     Eof,
 }
@@ -50,6 +54,9 @@ impl fmt::Display for TokenType {
             TokenType::RBrack => write!(f, "RBrack"),
             TokenType::Comma => write!(f, "Comma"),
             TokenType::Dot => write!(f, "Dot"),
+            TokenType::DotDot => write!(f, "DotDot"),
+            TokenType::DotDotEqual => write!(f, "DotDotEqual"),
+            TokenType::DotDotDot => write!(f, "DotDotDot"),
             TokenType::Minus => write!(f, "Minus"),
             TokenType::Plus => write!(f, "Plus"),
             TokenType::Semicolon => write!(f, "Semicolon"),
@@ -58,24 +65,52 @@ impl fmt::Display for TokenType {
             TokenType::Asterisk => write!(f, "Asterisk"),
             TokenType::Incr => write!(f, "Incr"),
             TokenType::Decr => write!(f, "Decr"),
+            TokenType::Percent => write!(f, "Percent"),
+            TokenType::AsteriskAsterisk => write!(f, "AsteriskAsterisk"),
+            TokenType::FSlashFSlash => write!(f, "FSlashFSlash"),
+            TokenType::Ampersand => write!(f, "Ampersand"),
+            TokenType::Pipe => write!(f, "Pipe"),
+            TokenType::Caret => write!(f, "Caret"),
+            TokenType::Tilde => write!(f, "Tilde"),
+            TokenType::LessLess => write!(f, "LessLess"),
+            TokenType::GreaterGreater => write!(f, "GreaterGreater"),
+            TokenType::Question => write!(f, "Question"),
+            TokenType::QuestionQuestion => write!(f, "QuestionQuestion"),
+            TokenType::QuestionDot => write!(f, "QuestionDot"),
+            TokenType::QuestionLBrack => write!(f, "QuestionLBrack"),
             TokenType::Bang => write!(f, "Bang"),
             TokenType::BangEqual => write!(f, "BangEqual"),
             TokenType::Equal => write!(f, "Equal"),
             TokenType::EqualEqual => write!(f, "EqualEqual"),
+            TokenType::Arrow => write!(f, "Arrow"),
+            TokenType::PlusEqual => write!(f, "PlusEqual"),
+            TokenType::MinusEqual => write!(f, "MinusEqual"),
+            TokenType::AsteriskEqual => write!(f, "AsteriskEqual"),
+            TokenType::FSlashEqual => write!(f, "FSlashEqual"),
+            TokenType::PercentEqual => write!(f, "PercentEqual"),
             TokenType::Greater => write!(f, "Greater"),
             TokenType::GreaterEqual => write!(f, "GreaterEqual"),
             TokenType::Less => write!(f, "Less"),
             TokenType::LessEqual => write!(f, "LessEqual"),
             TokenType::Identifier => write!(f, "Identifier"),
             TokenType::String => write!(f, "String"),
+            TokenType::Int => write!(f, "Int"),
             TokenType::Num => write!(f, "Num"),
             TokenType::And => write!(f, "And"),
+            TokenType::As => write!(f, "As"),
             TokenType::Class => write!(f, "Class"),
+            TokenType::Const => write!(f, "Const"),
             TokenType::Else => write!(f, "Else"),
+            TokenType::Eprint => write!(f, "Eprint"),
+            TokenType::Export => write!(f, "Export"),
             TokenType::False => write!(f, "False"),
             TokenType::For => write!(f, "For"),
+            TokenType::From => write!(f, "From"),
             TokenType::Def => write!(f, "Def"),
             TokenType::If => write!(f, "If"),
+            TokenType::Import => write!(f, "Import"),
+            TokenType::In => write!(f, "In"),
+            TokenType::Match => write!(f, "Match"),
             TokenType::Null => write!(f, "Null"),
             TokenType::Or => write!(f, "Or"),
             TokenType::Print => write!(f, "Print"),
@@ -85,6 +120,7 @@ impl fmt::Display for TokenType {
             TokenType::True => write!(f, "True"),
             TokenType::Var => write!(f, "var"),
             TokenType::While => write!(f, "While"),
+            TokenType::Yield => write!(f, "Yield"),
             TokenType::Eof => write!(f, "Eof"),
         }
     }
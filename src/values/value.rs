@@ -1,31 +1,177 @@
-use std::fmt;
+use std::{cmp::Ordering, fmt};
 
-use crate::{callable::{Func, NativeFunc}, list::List};
+use crate::{
+    bigint::BigInt,
+    callable::{Func, NativeFunc},
+    error::InterpreterError,
+    generator::Generator,
+    list::List,
+    module::Module,
+    number::Number,
+    range::Range,
+};
 
+/// Lists nested deeper than this are shown as `[...]` by `Value::repr`
+const REPR_MAX_DEPTH: usize = 8;
+
+/// The most items of a list shown by `Value::repr`, after which the rest are counted instead
+const REPR_MAX_ITEMS: usize = 100;
+
+// Functions are much larger than the other variants, but are kept unboxed as they are matched on
+// all over the interpreter
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
 pub enum Value {
     Function(Func),
+    Generator(Generator),
     List(List),
     Literal(LiteralType),
+    Module(Module),
     NativeFunction(NativeFunc),
+    Range(Range),
 }
 
-#[derive(Clone, Debug, PartialEq, PartialOrd)]
+/// `Num` is a float, while whole numbers are stored as an `Int`, or as a `BigInt` once they no
+/// longer fit in an `i64`
+#[derive(Clone, Debug)]
 pub enum LiteralType {
     Str(String),
     Num(f64),
+    Int(i64),
+    BigInt(BigInt),
     True,
     False,
     Null
 }
 
+impl Value {
+    /// The name of the value's type, as given by the `type` builtin and shown in error messages.
+    /// Ints and floats are both `num`
+    pub fn type_name(&self) -> &'static str {
+        return match self {
+            Value::Function(_) => "function",
+            Value::Generator(_) => "generator",
+            Value::List(_) => "list",
+            Value::Literal(literal) => match literal {
+                LiteralType::Str(_) => "str",
+                LiteralType::Num(_) | LiteralType::Int(_) | LiteralType::BigInt(_) => "num",
+                LiteralType::True | LiteralType::False => "bool",
+                LiteralType::Null => "null",
+            },
+            Value::Module(_) => "module",
+            Value::NativeFunction(_) => "native",
+            Value::Range(_) => "range",
+        };
+    }
+
+    /// Shows the value unambiguously, for debugging. Unlike its `Display`, strings are quoted and
+    /// escaped and generators and modules are shown in angle brackets like functions, while
+    /// numbers are shown the same way. Lists are cut short once they are too deep or too long.
+    ///
+    /// Lists hold copies of their items rather than references, so a list can never contain
+    /// itself and there are no cycles to guard against.
+    pub fn repr(&self) -> String {
+        let mut out = String::new();
+        self.write_repr(&mut out, 0);
+        return out;
+    }
+
+    fn write_repr(&self, out: &mut String, depth: usize) {
+        match self {
+            Value::Literal(LiteralType::Str(s)) => {
+                out.push('"');
+                for c in s.chars() {
+                    match c {
+                        '"' => out.push_str("\\\""),
+                        '\\' => out.push_str("\\\\"),
+                        '\n' => out.push_str("\\n"),
+                        '\r' => out.push_str("\\r"),
+                        '\t' => out.push_str("\\t"),
+                        c => out.push(c),
+                    }
+                }
+                out.push('"');
+            }
+            Value::List(_) if depth >= REPR_MAX_DEPTH => out.push_str("[...]"),
+            Value::List(list) => {
+                out.push('[');
+                for (i, item) in list.values.iter().take(REPR_MAX_ITEMS).enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    item.write_repr(out, depth + 1);
+                }
+                if list.len() > REPR_MAX_ITEMS {
+                    out.push_str(&format!(", ... {} more", list.len() - REPR_MAX_ITEMS));
+                }
+                out.push(']');
+            }
+            Value::Generator(generator) => out.push_str(&format!("<generator {}>", generator.name())),
+            Value::Module(module) => out.push_str(&format!("<module {}>", module.name())),
+            _ => out.push_str(&self.to_string()),
+        }
+    }
+
+    /// Whether the value counts as true in a condition. Only `null` and `false` are falsy, so `0`,
+    /// `""` and `[]` are all truthy, as is every function, generator, module and range
+    pub fn is_truthy(&self) -> bool {
+        return !matches!(self, Value::Literal(LiteralType::Null | LiteralType::False));
+    }
+
+    /// The natural ordering used when sorting. Strings and bools can only be compared with values
+    /// of the same type, ints and floats can be compared with each other, and lists are compared item by item, so any other pair of
+    /// values, including `NaN`, cannot be ordered
+    pub fn compare(&self, other: &Value) -> Result<Ordering, InterpreterError> {
+        match (self, other) {
+            (Value::Literal(a), Value::Literal(b)) => match (a, b) {
+                (
+                    LiteralType::Num(_) | LiteralType::Int(_) | LiteralType::BigInt(_),
+                    LiteralType::Num(_) | LiteralType::Int(_) | LiteralType::BigInt(_),
+                ) => {
+                    if let Some(ordering) = a.partial_cmp(b) {
+                        return Ok(ordering);
+                    }
+                },
+                (LiteralType::Str(s1), LiteralType::Str(s2)) => return Ok(s1.cmp(s2)),
+                (
+                    LiteralType::True | LiteralType::False,
+                    LiteralType::True | LiteralType::False,
+                ) => {
+                    let b1 = *a == LiteralType::True;
+                    let b2 = *b == LiteralType::True;
+                    return Ok(b1.cmp(&b2));
+                },
+                _ => {},
+            },
+            (Value::List(l1), Value::List(l2)) => {
+                for (v1, v2) in l1.values.iter().zip(l2.values.iter()) {
+                    let ordering = v1.compare(v2)?;
+                    if ordering != Ordering::Equal {
+                        return Ok(ordering);
+                    }
+                }
+                return Ok(l1.len().cmp(&l2.len()));
+            },
+            _ => {},
+        }
+
+        return Err(InterpreterError::CannotCompareTypes {
+            left: self.type_name().to_string(),
+            right: other.type_name().to_string(),
+        });
+    }
+}
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         return match self {
-            Value::Function(fun) => write!(f, "Function({fun})"),
+            Value::Function(fun) => write!(f, "{fun}"),
+            Value::Generator(generator) => write!(f, "{generator}"),
             Value::List(list) => write!(f, "{list}"),
             Value::Literal(literal) => write!(f, "{literal}"),
-            Value::NativeFunction(nf) => write!(f, "NativeFunction({nf})"),
+            Value::Module(module) => write!(f, "{module}"),
+            Value::NativeFunction(nf) => write!(f, "{nf}"),
+            Value::Range(range) => write!(f, "{range}"),
         };
     }
 }
@@ -34,10 +180,44 @@ impl fmt::Display for LiteralType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         return match self {
             LiteralType::Str(s) => write!(f, "{s}"),
-            LiteralType::Num(n) => write!(f, "{n}"),
+            // Debug formatting keeps the `.0` on whole floats, so that `1.0` is not shown as an int
+            LiteralType::Num(n) => write!(f, "{n:?}"),
+            LiteralType::Int(n) => write!(f, "{n}"),
+            LiteralType::BigInt(n) => write!(f, "{n}"),
             LiteralType::True => write!(f, "true"),
             LiteralType::False => write!(f, "false"),
             LiteralType::Null => write!(f, "null")
         };
     }
-}
\ No newline at end of file
+}
+
+/// Numbers are compared by value whatever their type, so `1 == 1.0`
+impl PartialEq for LiteralType {
+    fn eq(&self, other: &Self) -> bool {
+        if let (Some(a), Some(b)) = (Number::from_literal(self), Number::from_literal(other)) {
+            return a == b;
+        }
+
+        return match (self, other) {
+            (LiteralType::Str(s1), LiteralType::Str(s2)) => s1 == s2,
+            (LiteralType::True, LiteralType::True)
+            | (LiteralType::False, LiteralType::False)
+            | (LiteralType::Null, LiteralType::Null) => true,
+            _ => false,
+        };
+    }
+}
+
+impl PartialOrd for LiteralType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if let (Some(a), Some(b)) = (Number::from_literal(self), Number::from_literal(other)) {
+            return a.partial_cmp(&b);
+        }
+
+        return match (self, other) {
+            (LiteralType::Str(s1), LiteralType::Str(s2)) => s1.partial_cmp(s2),
+            _ if self == other => Some(Ordering::Equal),
+            _ => None,
+        };
+    }
+}